@@ -1,13 +1,13 @@
 use clap::{ArgAction, Args, Parser, Subcommand};
 
 use goxlr_types::{
-    AnimationMode, Button, ButtonColourGroups, ButtonColourOffStyle, ChannelName,
-    CompressorAttackTime, CompressorRatio, CompressorReleaseTime, EchoStyle, EffectBankPresets,
-    EncoderColourTargets, EqFrequencies, FaderDisplayStyle, FaderName, GateTimes, GenderStyle,
-    HardTuneSource, HardTuneStyle, InputDevice, MegaphoneStyle, MiniEqFrequencies, Mix,
-    MuteFunction, MuteState, OutputDevice, PitchStyle, ReverbStyle, RobotRange, RobotStyle,
-    SampleBank, SampleButtons, SamplePlayOrder, SamplePlaybackMode, SimpleColourTargets,
-    WaterfallDirection,
+    AnimationMode, Button, ButtonAction, ButtonColourGroups, ButtonColourOffStyle, ChannelName,
+    CompressorAttackTime, CompressorRatio, CompressorReleaseTime, DuckingSource, EchoStyle,
+    EffectBankPresets, EncoderColourTargets, EqFrequencies, FaderDisplayStyle, FaderName,
+    GateTimes, GenderStyle, HardTuneSource, HardTuneStyle, InputDevice, MegaphoneStyle,
+    MiniEqFrequencies, Mix, MuteFunction, MuteState, OutputDevice, PitchStyle, ReverbStyle,
+    RobotRange, RobotStyle, SampleBank, SampleButtons, SamplePlayOrder, SamplePlaybackMode,
+    SimpleColourTargets, VolumeCurve, WaterfallDirection,
 };
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -92,6 +92,46 @@ pub enum SubCommands {
         volume_percent: u8,
     },
 
+    /// Raise or Lower a Channel Volume
+    AdjustVolume {
+        /// The Channel To Change
+        #[arg(value_enum)]
+        channel: ChannelName,
+
+        /// The change as a percentage of the full range [-100 - 100]
+        #[arg(allow_hyphen_values = true)]
+        percent: i8,
+    },
+
+    /// Smoothly move a Channel Volume to a target
+    RampVolume {
+        /// The Channel To Change
+        #[arg(value_enum)]
+        channel: ChannelName,
+
+        /// The target volume as a percentage [0 - 100]
+        #[arg(value_parser=percent_value)]
+        volume_percent: u8,
+
+        /// How long the ramp should take in milliseconds
+        duration: u32,
+
+        /// The curve to follow to the target
+        #[arg(value_enum, default_value_t = VolumeCurve::Linear)]
+        curve: VolumeCurve,
+    },
+
+    /// Mute a Channel, regardless of whether it's on a fader
+    Mute {
+        /// The Channel To Change
+        #[arg(value_enum)]
+        channel: ChannelName,
+
+        /// Where to mute the channel to, leave empty to unmute
+        #[arg(value_enum)]
+        function: Option<MuteFunction>,
+    },
+
     /// Adjust Submix Settings
     Submix {
         #[command(subcommand)]
@@ -153,6 +193,34 @@ pub enum SubCommands {
         #[clap[subcommand]]
         command: DeviceSettings,
     },
+
+    /// Press, Release or Hold a button as if it was used on the GoXLR
+    Button {
+        #[arg(value_enum)]
+        button: Button,
+
+        #[arg(value_enum)]
+        action: ButtonAction,
+    },
+
+    /// Instantly remove everything from the Stream, and put it back again
+    Panic {
+        /// Whether the Panic is active
+        #[arg(value_parser, action = ArgAction::Set)]
+        active: bool,
+    },
+
+    /// Save, Recall and Delete Mixer Scenes
+    Scenes {
+        #[command(subcommand)]
+        command: SceneCommands,
+    },
+
+    /// Undo the last change to the profile
+    Undo,
+
+    /// Redo the last undone change to the profile
+    Redo,
 }
 
 fn percent_value(s: &str) -> Result<u8, String> {
@@ -208,12 +276,29 @@ pub enum CoughButtonBehaviours {
         mute_behaviour: MuteFunction,
     },
 
+    /// Mute to a custom set of outputs on Press (leave empty to use the Mute Target)
+    MuteTargets {
+        #[arg(value_enum)]
+        targets: Vec<OutputDevice>,
+    },
+
     /// Change the current button Mute State
     MuteState {
         /// The new State
         #[arg(value_enum)]
         state: MuteState,
     },
+
+    /// Mute the Mic for a number of seconds, then unmute it again
+    MuteTimer {
+        /// The duration in Seconds (0 cancels a running timer)
+        #[arg(value_parser, action = ArgAction::Set)]
+        seconds: u32,
+
+        /// Announce the unmute shortly before it happens
+        #[arg(long)]
+        announce: bool,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -232,6 +317,25 @@ pub enum ProfileType {
     },
 }
 
+#[derive(Subcommand, Debug)]
+#[command(arg_required_else_help = true)]
+pub enum SceneCommands {
+    /// Save the current volumes, mutes and routing as a scene
+    Save { scene_name: String },
+
+    /// Recall a scene, fading the volumes over the duration
+    Recall {
+        scene_name: String,
+
+        /// The fade duration in milliseconds
+        #[arg(default_value = "0")]
+        duration: u32,
+    },
+
+    /// Delete a saved scene
+    Delete { scene_name: String },
+}
+
 #[derive(Subcommand, Debug)]
 #[command(arg_required_else_help = true)]
 pub enum ProfileAction {
@@ -324,6 +428,24 @@ pub enum SubmixCommands {
         volume_percent: u8,
     },
 
+    /// Smoothly move a Submix Volume to a target
+    RampVolume {
+        /// The Channel To Change
+        #[arg(value_enum)]
+        channel: ChannelName,
+
+        /// The target volume as a percentage [0 - 100]
+        #[arg(value_parser=percent_value)]
+        volume_percent: u8,
+
+        /// How long the ramp should take in milliseconds
+        duration: u32,
+
+        /// The curve to follow to the target
+        #[arg(value_enum, default_value_t = VolumeCurve::Linear)]
+        curve: VolumeCurve,
+    },
+
     /// Link / Unlink a volume -> submix volume
     Linked {
         /// The Channel to Change        
@@ -486,6 +608,16 @@ pub enum FaderCommands {
         mute_behaviour: MuteFunction,
     },
 
+    /// Mute a Fader to a custom set of outputs (leave empty to use the Mute Behaviour)
+    MuteTargets {
+        /// The Fader to Change
+        #[arg(value_enum)]
+        fader: FaderName,
+
+        #[arg(value_enum)]
+        targets: Vec<OutputDevice>,
+    },
+
     /// Sets the Current Mute State of the Fader
     MuteState {
         /// The Fader to Change
@@ -497,6 +629,21 @@ pub enum FaderCommands {
         state: MuteState,
     },
 
+    /// Mute a Fader for a number of seconds, then unmute it again
+    MuteTimer {
+        /// The Fader to Mute
+        #[arg(value_enum)]
+        fader: FaderName,
+
+        /// The duration in Seconds (0 cancels a running timer)
+        #[arg(value_parser, action = ArgAction::Set)]
+        seconds: u32,
+
+        /// Announce the unmute shortly before it happens
+        #[arg(long)]
+        announce: bool,
+    },
+
     /// Configure the Scribble Screen on a Fader
     Scribbles {
         #[command(subcommand)]
@@ -1175,10 +1322,126 @@ pub enum DeviceSettings {
         enabled: bool,
     },
 
+    /// Whether a Panic also removes everything from the Chat Mic
+    PanicMutesChat {
+        /// Whether the setting is enabled
+        #[arg(value_parser, action = ArgAction::Set)]
+        enabled: bool,
+    },
+
     /// Locks the Faders to their current value on MuteToAll
     LockFaders {
         /// Whether the setting is enabled
         #[arg(value_parser, action = ArgAction::Set)]
         enabled: bool,
     },
+
+    /// Enable detection of whether the Microphone is being spoken into
+    VoiceActivityEnabled {
+        /// Whether the setting is enabled
+        #[arg(value_parser, action = ArgAction::Set)]
+        enabled: bool,
+    },
+
+    /// The Mic Level needed to be considered as speaking [-72 - 0]
+    VoiceActivityThreshold {
+        /// The Threshold in dB
+        #[arg(allow_hyphen_values = true)]
+        threshold: i8,
+    },
+
+    /// How long the Mic Level must be above the threshold before speaking is detected
+    VoiceActivityAttack {
+        /// The duration in Milliseconds
+        #[arg(value_parser, action = ArgAction::Set)]
+        attack: u16,
+    },
+
+    /// How long the Mic Level must be below the threshold before speaking has stopped
+    VoiceActivityHold {
+        /// The duration in Milliseconds
+        #[arg(value_parser, action = ArgAction::Set)]
+        hold: u16,
+    },
+
+    /// Lower other channels while the Ducking source is active
+    DuckingEnabled {
+        /// Whether the setting is enabled
+        #[arg(value_parser, action = ArgAction::Set)]
+        enabled: bool,
+    },
+
+    /// What causes the target channels to be ducked
+    DuckingSource {
+        /// The Ducking source
+        #[arg(value_enum)]
+        source: DuckingSource,
+    },
+
+    /// The channel which causes ducking when the source is Input
+    DuckingInput {
+        /// The input channel
+        #[arg(value_enum)]
+        channel: ChannelName,
+    },
+
+    /// Sets whether a channel is lowered while ducking
+    DuckingTarget {
+        /// The channel to change
+        #[arg(value_enum)]
+        channel: ChannelName,
+
+        /// Whether the channel should be ducked
+        #[arg(value_parser, action = ArgAction::Set)]
+        enabled: bool,
+    },
+
+    /// How much to lower the target channels by [0 - 100]
+    DuckingAmount {
+        /// The reduction as a percentage of the channel volume
+        #[arg(value_parser, action = ArgAction::Set)]
+        amount: u8,
+    },
+
+    /// How long it takes to fully duck the target channels
+    DuckingAttack {
+        /// The duration in Milliseconds
+        #[arg(value_parser, action = ArgAction::Set)]
+        attack: u16,
+    },
+
+    /// How long it takes for the target channels to return to their volume
+    DuckingRelease {
+        /// The duration in Milliseconds
+        #[arg(value_parser, action = ArgAction::Set)]
+        release: u16,
+    },
+
+    /// Starts or stops ducking when the source is Manual
+    DuckingTriggered {
+        /// Whether ducking is triggered
+        #[arg(value_parser, action = ArgAction::Set)]
+        triggered: bool,
+    },
+
+    /// Keep the Mic muted unless the Cough button is held
+    PushToTalkEnabled {
+        /// Whether the setting is enabled
+        #[arg(value_parser, action = ArgAction::Set)]
+        enabled: bool,
+    },
+
+    /// How long the Mic stays open after the Cough button is released
+    PushToTalkReleaseTail {
+        /// The duration in Milliseconds
+        #[arg(value_parser, action = ArgAction::Set)]
+        release_tail: u16,
+    },
+
+    /// Double pressing the Cough button keeps the Mic open until it's next pressed
+    PushToTalkLatch {
+        /// Whether the setting is enabled
+        #[arg(value_parser, action = ArgAction::Set)]
+        enabled: bool,
+    },
 }
@@ -3,8 +3,8 @@ use crate::cli::{
     CoughButtonBehaviours, Echo, EffectsCommands, EqualiserCommands, EqualiserMiniCommands,
     FaderCommands, FaderLightingCommands, FadersAllLightingCommands, FirmwareCommands, Gender,
     HardTune, LightingCommands, Megaphone, MicrophoneCommands, NoiseGateCommands, Pitch,
-    ProfileAction, ProfileType, Reverb, Robot, SamplerCommands, Scribbles, SubCommands,
-    SubmixCommands,
+    ProfileAction, ProfileType, Reverb, Robot, SamplerCommands, SceneCommands, Scribbles,
+    SubCommands, SubmixCommands,
 };
 use crate::cli::{Cli, DeviceSettings};
 use crate::microphone::apply_microphone_controls;
@@ -199,11 +199,23 @@ pub async fn run_cli() -> Result<()> {
                             )
                             .await?;
                     }
+                    FaderCommands::MuteTargets { fader, targets } => {
+                        let command = GoXLRCommand::SetFaderMuteTargets(*fader, targets.clone());
+                        client.command(&serial, command).await?;
+                    }
                     FaderCommands::MuteState { fader, state } => {
                         client
                             .command(&serial, GoXLRCommand::SetFaderMuteState(*fader, *state))
                             .await?;
                     }
+                    FaderCommands::MuteTimer {
+                        fader,
+                        seconds,
+                        announce,
+                    } => {
+                        let command = GoXLRCommand::SetFaderMuteTimer(*fader, *seconds, *announce);
+                        client.command(&serial, command).await?;
+                    }
                     FaderCommands::Scribbles { command } => match command {
                         Scribbles::Icon { fader, name } => {
                             client
@@ -258,6 +270,27 @@ pub async fn run_cli() -> Result<()> {
                         .command(&serial, GoXLRCommand::SetVolume(*channel, value as u8))
                         .await?;
                 }
+                SubCommands::AdjustVolume { channel, percent } => {
+                    client
+                        .command(&serial, GoXLRCommand::AdjustVolume(*channel, *percent))
+                        .await?;
+                }
+                SubCommands::RampVolume {
+                    channel,
+                    volume_percent,
+                    duration,
+                    curve,
+                } => {
+                    let value = (255 * *volume_percent as u16) / 100;
+                    let command =
+                        GoXLRCommand::RampVolume(*channel, value as u8, *duration, *curve);
+                    client.command(&serial, command).await?;
+                }
+                SubCommands::Mute { channel, function } => {
+                    client
+                        .command(&serial, GoXLRCommand::SetChannelMute(*channel, *function))
+                        .await?;
+                }
                 SubCommands::CoughButton { command } => match command {
                     CoughButtonBehaviours::ButtonIsHold { is_hold } => {
                         client
@@ -269,11 +302,24 @@ pub async fn run_cli() -> Result<()> {
                             .command(&serial, GoXLRCommand::SetCoughMuteFunction(*mute_behaviour))
                             .await?;
                     }
+                    CoughButtonBehaviours::MuteTargets { targets } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetCoughMuteTargets(targets.clone()))
+                            .await?;
+                    }
                     CoughButtonBehaviours::MuteState { state } => {
                         client
                             .command(&serial, GoXLRCommand::SetCoughMuteState(*state))
                             .await?;
                     }
+                    CoughButtonBehaviours::MuteTimer { seconds, announce } => {
+                        client
+                            .command(
+                                &serial,
+                                GoXLRCommand::SetCoughMuteTimer(*seconds, *announce),
+                            )
+                            .await?;
+                    }
                 },
                 SubCommands::BleepVolume { volume_percent } => {
                     // Ok, this is a value between -34 and 0, with 0 being loudest :D
@@ -977,6 +1023,21 @@ pub async fn run_cli() -> Result<()> {
                             )
                             .await?;
                     }
+                    SubmixCommands::RampVolume {
+                        channel,
+                        volume_percent,
+                        duration,
+                        curve,
+                    } => {
+                        let value = (255 * *volume_percent as u16) / 100;
+                        let command = GoXLRCommand::RampSubMixVolume(
+                            *channel,
+                            value as u8,
+                            *duration,
+                            *curve,
+                        );
+                        client.command(&serial, command).await?;
+                    }
                     SubmixCommands::Linked { channel, linked } => {
                         client
                             .command(&serial, GoXLRCommand::SetSubMixLinked(*channel, *linked))
@@ -993,6 +1054,45 @@ pub async fn run_cli() -> Result<()> {
                             .await?;
                     }
                 },
+                SubCommands::Button { button, action } => {
+                    client
+                        .command(&serial, GoXLRCommand::TriggerButton(*button, *action))
+                        .await?;
+                }
+                SubCommands::Panic { active } => {
+                    client
+                        .command(&serial, GoXLRCommand::SetPanicActive(*active))
+                        .await?;
+                }
+                SubCommands::Scenes { command } => match command {
+                    SceneCommands::Save { scene_name } => {
+                        client
+                            .command(&serial, GoXLRCommand::SaveScene(scene_name.to_string()))
+                            .await?;
+                    }
+                    SceneCommands::Recall {
+                        scene_name,
+                        duration,
+                    } => {
+                        client
+                            .command(
+                                &serial,
+                                GoXLRCommand::RecallScene(scene_name.to_string(), *duration),
+                            )
+                            .await?;
+                    }
+                    SceneCommands::Delete { scene_name } => {
+                        client
+                            .command(&serial, GoXLRCommand::DeleteScene(scene_name.to_string()))
+                            .await?;
+                    }
+                },
+                SubCommands::Undo => {
+                    client.command(&serial, GoXLRCommand::Undo()).await?;
+                }
+                SubCommands::Redo => {
+                    client.command(&serial, GoXLRCommand::Redo()).await?;
+                }
                 SubCommands::Settings { command } => match command {
                     DeviceSettings::MuteHoldDuration { duration } => {
                         client
@@ -1017,11 +1117,94 @@ pub async fn run_cli() -> Result<()> {
                             .command(&serial, GoXLRCommand::SetVCMuteAlsoMuteCM(*enabled))
                             .await?;
                     }
+                    DeviceSettings::PanicMutesChat { enabled } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetPanicMutesChat(*enabled))
+                            .await?;
+                    }
                     DeviceSettings::LockFaders { enabled } => {
                         client
                             .command(&serial, GoXLRCommand::SetLockFaders(*enabled))
                             .await?;
                     }
+                    DeviceSettings::VoiceActivityEnabled { enabled } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetVoiceActivityEnabled(*enabled))
+                            .await?;
+                    }
+                    DeviceSettings::VoiceActivityThreshold { threshold } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetVoiceActivityThreshold(*threshold))
+                            .await?;
+                    }
+                    DeviceSettings::VoiceActivityAttack { attack } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetVoiceActivityAttack(*attack))
+                            .await?;
+                    }
+                    DeviceSettings::VoiceActivityHold { hold } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetVoiceActivityHold(*hold))
+                            .await?;
+                    }
+                    DeviceSettings::DuckingEnabled { enabled } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetDuckingEnabled(*enabled))
+                            .await?;
+                    }
+                    DeviceSettings::DuckingSource { source } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetDuckingSource(*source))
+                            .await?;
+                    }
+                    DeviceSettings::DuckingInput { channel } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetDuckingInput(*channel))
+                            .await?;
+                    }
+                    DeviceSettings::DuckingTarget { channel, enabled } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetDuckingTarget(*channel, *enabled))
+                            .await?;
+                    }
+                    DeviceSettings::DuckingAmount { amount } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetDuckingAmount(*amount))
+                            .await?;
+                    }
+                    DeviceSettings::DuckingAttack { attack } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetDuckingAttack(*attack))
+                            .await?;
+                    }
+                    DeviceSettings::DuckingRelease { release } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetDuckingRelease(*release))
+                            .await?;
+                    }
+                    DeviceSettings::DuckingTriggered { triggered } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetDuckingTriggered(*triggered))
+                            .await?;
+                    }
+                    DeviceSettings::PushToTalkEnabled { enabled } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetPushToTalkEnabled(*enabled))
+                            .await?;
+                    }
+                    DeviceSettings::PushToTalkReleaseTail { release_tail } => {
+                        client
+                            .command(
+                                &serial,
+                                GoXLRCommand::SetPushToTalkReleaseTail(*release_tail),
+                            )
+                            .await?;
+                    }
+                    DeviceSettings::PushToTalkLatch { enabled } => {
+                        client
+                            .command(&serial, GoXLRCommand::SetPushToTalkLatch(*enabled))
+                            .await?;
+                    }
                 },
                 SubCommands::Firmware { command } => match command {
                     FirmwareCommands::FirmwareUpdate { path } => {
@@ -0,0 +1,243 @@
+use crate::history::ProfileSnapshot;
+use goxlr_ipc::Scene;
+use std::path::Path;
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+/*
+   Keeps a list of running processes for matching App Rules against. The list is shared by all
+   devices, and is only refreshed the first time it's needed after being invalidated, so devices
+   without any rules don't cost a process scan.
+*/
+pub struct AppWatcher {
+    system: System,
+    stale: bool,
+}
+
+impl Default for AppWatcher {
+    fn default() -> Self {
+        Self {
+            system: System::new(),
+            stale: true,
+        }
+    }
+}
+
+impl AppWatcher {
+    pub fn invalidate(&mut self) {
+        self.stale = true;
+    }
+
+    /// Checks for a running process, paths are matched against the executable, anything else
+    /// against the process name.
+    pub fn is_running(&mut self, process: &str) -> bool {
+        if self.stale {
+            let refresh_kind = ProcessRefreshKind::nothing().with_exe(UpdateKind::OnlyIfNotSet);
+            self.system
+                .refresh_processes_specifics(ProcessesToUpdate::All, true, refresh_kind);
+            self.stale = false;
+        }
+
+        let is_path = process.contains(['/', '\\']);
+        self.system.processes().values().any(|running| {
+            if is_path {
+                running.exe() == Some(Path::new(process))
+            } else {
+                running
+                    .name()
+                    .to_string_lossy()
+                    .eq_ignore_ascii_case(process)
+            }
+        })
+    }
+}
+
+// The parts of the device an App Rule changed, so they can be put back afterwards. The profiles
+// are restored from the snapshot taken when the rule started, so unsaved changes aren't lost.
+#[derive(Default)]
+pub struct AppRestore {
+    pub profile: Option<ProfileSnapshot>,
+    pub mic_profile: Option<ProfileSnapshot>,
+    pub scene: Option<Scene>,
+}
+
+pub struct ActiveAppRule {
+    pub name: String,
+    pub restore: AppRestore,
+
+    // The names of the profiles this rule loaded, if any
+    pub profile: Option<String>,
+    pub mic_profile: Option<String>,
+}
+
+/// Checks whether the profiles have been changed by hand since the active rules loaded them,
+/// in which case putting the old ones back would throw away the user's choice.
+pub fn switched_by_hand(active: &[ActiveAppRule], profile: &str, mic_profile: &str) -> bool {
+    let loaded_profile = active.iter().rev().find_map(|rule| rule.profile.as_ref());
+    let loaded_mic_profile = active
+        .iter()
+        .rev()
+        .find_map(|rule| rule.mic_profile.as_ref());
+
+    loaded_profile.is_some_and(|loaded| loaded != profile)
+        || loaded_mic_profile.is_some_and(|loaded| loaded != mic_profile)
+}
+
+/// Removes a rule from the active list, returning what should be restored now. If a rule that
+/// started later also changed something, it gets our restore value instead, so it puts back
+/// the state from before either app was opened when it finishes.
+pub fn deactivate(active: &mut Vec<ActiveAppRule>, name: &str) -> AppRestore {
+    let Some(index) = active.iter().position(|rule| rule.name == name) else {
+        return AppRestore::default();
+    };
+
+    let removed = active.remove(index);
+    let later = &mut active[index..];
+    AppRestore {
+        profile: hand_down(removed.restore.profile, later, |r| &mut r.profile),
+        mic_profile: hand_down(removed.restore.mic_profile, later, |r| &mut r.mic_profile),
+        scene: hand_down(removed.restore.scene, later, |r| &mut r.scene),
+    }
+}
+
+fn hand_down<T>(
+    value: Option<T>,
+    later: &mut [ActiveAppRule],
+    field: fn(&mut AppRestore) -> &mut Option<T>,
+) -> Option<T> {
+    let value = value?;
+    for rule in later {
+        let slot = field(&mut rule.restore);
+        if slot.is_some() {
+            *slot = Some(value);
+            return None;
+        }
+    }
+    Some(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(name: &str) -> ProfileSnapshot {
+        ProfileSnapshot {
+            profile_name: name.to_owned(),
+            profile: vec![],
+            mic_profile_name: name.to_owned(),
+            mic_profile: vec![],
+        }
+    }
+
+    fn rule(name: &str, profile: Option<&str>, mic_profile: Option<&str>) -> ActiveAppRule {
+        ActiveAppRule {
+            name: name.to_owned(),
+            restore: AppRestore {
+                profile: profile.map(snapshot),
+                mic_profile: mic_profile.map(snapshot),
+                scene: None,
+            },
+            profile: None,
+            mic_profile: None,
+        }
+    }
+
+    fn names(restore: &AppRestore) -> (Option<&str>, Option<&str>) {
+        (
+            restore.profile.as_ref().map(|s| s.profile_name.as_str()),
+            restore
+                .mic_profile
+                .as_ref()
+                .map(|s| s.mic_profile_name.as_str()),
+        )
+    }
+
+    #[test]
+    fn nested_rules_restore_in_order() {
+        let mut active = vec![
+            rule("first", Some("Original"), None),
+            rule("second", Some("First"), None),
+        ];
+
+        let restore = deactivate(&mut active, "second");
+        assert_eq!(names(&restore), (Some("First"), None));
+
+        let restore = deactivate(&mut active, "first");
+        assert_eq!(names(&restore), (Some("Original"), None));
+        assert!(active.is_empty());
+    }
+
+    #[test]
+    fn out_of_order_exit_hands_down_restore() {
+        let mut active = vec![
+            rule("first", Some("Original"), None),
+            rule("second", Some("First"), None),
+        ];
+
+        // The second app is still running, so nothing changes yet..
+        let restore = deactivate(&mut active, "first");
+        assert_eq!(names(&restore), (None, None));
+
+        // ..and once it closes, we go back to before either was opened.
+        let restore = deactivate(&mut active, "second");
+        assert_eq!(names(&restore), (Some("Original"), None));
+    }
+
+    #[test]
+    fn out_of_order_exit_only_hands_down_shared_parts() {
+        let mut active = vec![
+            rule("first", Some("Original"), Some("Original Mic")),
+            rule("second", None, Some("First Mic")),
+        ];
+
+        // The later rule only changed the mic profile, so the profile can go back now
+        let restore = deactivate(&mut active, "first");
+        assert_eq!(names(&restore), (Some("Original"), None));
+
+        let restore = deactivate(&mut active, "second");
+        assert_eq!(names(&restore), (None, Some("Original Mic")));
+    }
+
+    #[test]
+    fn hand_down_skips_rules_without_a_value() {
+        let mut later = vec![
+            rule("second", None, None),
+            rule("third", Some("Second"), None),
+        ];
+
+        let value = hand_down(Some(snapshot("Original")), &mut later, |r| &mut r.profile);
+        assert!(value.is_none());
+        assert!(later[0].restore.profile.is_none());
+        assert_eq!(names(&later[1].restore), (Some("Original"), None));
+
+        let value = hand_down(Some(snapshot("Original")), &mut later, |r| {
+            &mut r.mic_profile
+        });
+        assert_eq!(
+            value.map(|s| s.profile_name),
+            Some(String::from("Original"))
+        );
+        assert!(hand_down(None, &mut later, |r| &mut r.profile).is_none());
+    }
+
+    #[test]
+    fn unknown_rule_restores_nothing() {
+        let mut active = vec![rule("first", Some("Original"), None)];
+        let restore = deactivate(&mut active, "missing");
+        assert_eq!(names(&restore), (None, None));
+        assert_eq!(active.len(), 1);
+    }
+
+    #[test]
+    fn manual_switch_is_detected() {
+        let mut active = vec![rule("first", None, None), rule("second", None, None)];
+        active[0].profile = Some(String::from("Game"));
+        active[1].mic_profile = Some(String::from("Quiet"));
+
+        assert!(!switched_by_hand(&active, "Game", "Quiet"));
+        assert!(switched_by_hand(&active, "Other", "Quiet"));
+        assert!(switched_by_hand(&active, "Game", "Other"));
+
+        // Nothing loaded by a rule can't have been switched away from
+        assert!(!switched_by_hand(&[], "Other", "Other"));
+    }
+}
@@ -48,6 +48,18 @@ pub struct Cli {
     /// Force regular expression to use when finding the Sampler Output
     #[arg(long)]
     pub override_sample_output_device: Option<String>,
+
+    /// Start an emulated GoXLR, can be specified multiple times for multiple devices
+    #[arg(long, value_enum)]
+    pub emulate_device: Vec<EmulatedDevice>,
+
+    /// Record all USB traffic for connected devices into capture files in this directory
+    #[arg(long)]
+    pub capture_usb: Option<PathBuf>,
+
+    /// Start a device which replays responses from a USB capture file
+    #[arg(long)]
+    pub replay_capture: Vec<PathBuf>,
 }
 
 fn default_config_location() -> PathBuf {
@@ -57,6 +69,14 @@ fn default_config_location() -> PathBuf {
     proj_dirs.config_dir().join("settings.json")
 }
 
+#[derive(ValueEnum, Copy, Clone, Eq, PartialEq, Debug)]
+pub enum EmulatedDevice {
+    /// A Full sized GoXLR
+    Full,
+    /// A GoXLR Mini
+    Mini,
+}
+
 #[repr(usize)]
 #[derive(ValueEnum, Copy, Clone, Eq, PartialEq, Debug)]
 pub enum LevelFilter {
@@ -1,4 +1,4 @@
-use std::collections::{HashSet, VecDeque};
+use std::collections::{BTreeMap, HashSet, VecDeque};
 use std::path::PathBuf;
 use std::time::Duration;
 
@@ -13,15 +13,17 @@ use tokio::sync::mpsc::Sender;
 use tokio::time::Instant;
 
 use goxlr_ipc::{
-    Display, FaderStatus, GoXLRCommand, HardwareStatus, Levels, MicSettings, MixerStatus,
-    SampleProcessState, Settings,
+    ButtonBinding, DaemonCommand, Display, Ducking, FaderStatus, GoXLRCommand, HardwareStatus,
+    Levels, MicSettings, MixerStatus, PushToTalk, RuleEffect, SampleProcessState, Scene, Settings,
+    VoiceActivity,
 };
 use goxlr_profile_loader::components::mute::MuteFunction;
 use goxlr_types::{
-    Button, ChannelName, DeviceType, DisplayModeComponents, EffectBankPresets, EffectKey,
-    EncoderName, FaderName, HardTuneSource, InputDevice as BasicInputDevice, MicrophoneParamKey,
-    Mix, MuteState, OutputDevice as BasicOutputDevice, RobotRange, SampleBank, SampleButtons,
-    SamplePlaybackMode, VersionNumber, VodMode, WaterfallDirection,
+    Button, ButtonAction, ChannelName, DeviceType, DisplayModeComponents, DuckingSource,
+    EffectBankPresets, EffectKey, EncoderName, FaderName, HardTuneSource,
+    InputDevice as BasicInputDevice, MicrophoneParamKey, Mix, MuteFunction as BasicMuteFunction,
+    MuteState, OutputDevice as BasicOutputDevice, RobotRange, SampleBank, SampleButtons,
+    SamplePlaybackMode, SubMixChannelName, VersionNumber, VodMode, WaterfallDirection,
 };
 use goxlr_usb::animation::{AnimationMode, WaterFallDir};
 use goxlr_usb::buttonstate::{ButtonStates, Buttons};
@@ -31,23 +33,44 @@ use goxlr_usb::device::base::FullGoXLRDevice;
 use goxlr_usb::routing::{InputDevice, OutputDevice};
 
 use crate::SettingsHandle;
+use crate::app_rules::{ActiveAppRule, AppRestore, AppWatcher, deactivate, switched_by_hand};
 use crate::audio::{AudioFile, AudioHandler};
+use crate::ducking::DuckingEngine;
 use crate::events::EventTriggers;
 use crate::events::EventTriggers::TTSMessage;
 use crate::files::find_file_in_path;
 use crate::firmware::firmware_update::{
     FirmwareMessages, HardwareProgressResponse, ProgressResponse, ValidateUploadChunkResponse,
 };
+use crate::history::{History, HistoryEntry, ProfileSnapshot};
+use crate::links::{LinkCategory, LinkChange, LinkState, forwarded_category};
 use crate::mic_profile::{DEFAULT_MIC_PROFILE_NAME, MicProfileAdapter};
+use crate::mute_timer::MuteTimer;
 use crate::profile::{
-    DEFAULT_PROFILE_NAME, ProfileAdapter, usb_to_standard_button, version_newer_or_equal_to,
+    DEFAULT_PROFILE_NAME, ProfileAdapter, standard_to_usb_button, usb_to_standard_button,
+    version_newer_or_equal_to,
 };
+use crate::push_to_talk::PushToTalkEngine;
+use crate::ramps::VolumeRamp;
+use crate::rules::{RuleState, is_triggered, most_muted};
+use crate::scenes::{SceneFade, VolumeFade};
+use crate::voice_activity::VoiceActivityDetector;
+
+// How long after releasing a button a second press counts as a double press
+const DOUBLE_PRESS_WINDOW: Duration = Duration::from_millis(300);
 
 pub struct Device<'a> {
     goxlr: Box<dyn FullGoXLRDevice>,
     hardware: HardwareStatus,
     last_buttons: EnumSet<Buttons>,
+    physical_buttons: EnumSet<Buttons>,
+    virtual_buttons: EnumSet<Buttons>,
+    virtual_holds: EnumSet<Buttons>,
     button_states: EnumMap<Buttons, ButtonState>,
+    pending_clicks: EnumMap<Buttons, Option<Instant>>,
+
+    // A copy of the bindings from the settings, these are checked on every button press
+    button_bindings: BTreeMap<Button, ButtonBinding>,
     encoder_states: EnumMap<EncoderName, i8>,
     fader_last_seen: EnumMap<FaderName, u8>,
     fader_pause_until: EnumMap<FaderName, PauseUntil>,
@@ -56,11 +79,38 @@ pub struct Device<'a> {
     audio_handler: Option<AudioHandler>,
     hold_time: Duration,
     vc_mute_also_mute_cm: bool,
+    panic_mutes_chat: bool,
+    panic_active: bool,
     settings: &'a SettingsHandle,
     global_events: Sender<EventTriggers>,
 
     last_sample_error: Option<String>,
     tap_tempo: VecDeque<Instant>,
+    voice_activity: VoiceActivityDetector,
+    ducking: DuckingEngine,
+    push_to_talk: PushToTalkEngine,
+
+    // The last ducked volume sent for each channel, None if it's at its profile volume
+    ducked_volumes: EnumMap<ChannelName, Option<u8>>,
+    history: History,
+    scene_fade: Option<SceneFade>,
+    volume_ramps: EnumMap<ChannelName, Option<VolumeRamp>>,
+    submix_ramps: EnumMap<ChannelName, Option<VolumeRamp>>,
+    fader_mute_timers: EnumMap<FaderName, Option<MuteTimer>>,
+    cough_mute_timer: Option<MuteTimer>,
+
+    // The state as of the last time the rules were checked, None until the first check
+    rule_state: Option<RuleState>,
+
+    // Counts profile loads, so rules can see a reload of the current profile as well
+    profile_loads: u32,
+
+    // App Rules currently in effect, in the order they started
+    active_app_rules: Vec<ActiveAppRule>,
+
+    // Changes to pass on to linked devices
+    link_state: Option<LinkState>,
+    link_commands: Vec<(LinkCategory, LinkChange)>,
 }
 
 #[derive(Debug, Default, Copy, Clone)]
@@ -73,6 +123,7 @@ struct PauseUntil {
 struct ButtonState {
     press_time: Option<Instant>,
     hold_handled: bool,
+    binding_handled: bool,
 }
 
 // Used when loading profiles to provide the previous
@@ -85,12 +136,20 @@ pub(crate) struct CurrentState {
     pub(crate) volumes: EnumMap<ChannelName, u8>,
 }
 
+// The in-memory profiles of a device which has dropped off the bus, these are held for a short
+// time in case it comes back, so it can be restored without losing any unsaved changes.
+pub struct RecoverableState {
+    snapshot: ProfileSnapshot,
+    panic_active: bool,
+}
+
 impl<'a> Device<'a> {
     pub async fn new(
         goxlr: Box<dyn FullGoXLRDevice>,
         hardware: HardwareStatus,
         settings_handle: &'a SettingsHandle,
         global_events: Sender<EventTriggers>,
+        recovered: Option<&RecoverableState>,
     ) -> Result<Device<'a>> {
         debug!("New Device Loading..");
 
@@ -100,23 +159,142 @@ impl<'a> Device<'a> {
         }
 
         let serial = hardware.serial_number.clone();
-        let profile_name = settings_handle.get_device_profile_name(&serial).await;
-        let mic_profile = settings_handle.get_device_mic_profile_name(&serial).await;
 
-        let profile_name = profile_name.unwrap_or_else(|| DEFAULT_PROFILE_NAME.to_string());
-        let mic_name = mic_profile.unwrap_or_else(|| DEFAULT_MIC_PROFILE_NAME.to_string());
+        // If this device has only just dropped off the bus, carry on with the profiles we had
+        // in memory rather than reloading them, so any unsaved changes are kept. Panic stays on
+        // even if the profiles can't be recovered, nothing should reach the stream by surprise.
+        let panic_active = recovered.is_some_and(|state| state.panic_active);
+        let recovered = recovered.and_then(|state| match Self::recover_profiles(state) {
+            Ok(profiles) => Some(profiles),
+            Err(error) => {
+                warn!("Unable to Recover Previous State: {}", error);
+                None
+            }
+        });
+        let (profile, mic_profile) = match recovered {
+            Some(profiles) => {
+                info!("Recovering Previous State for GoXLR{}", device_type);
+                profiles
+            }
+            None => (
+                Self::load_profile(settings_handle, &serial).await,
+                Self::load_mic_profile(settings_handle, &serial).await,
+            ),
+        };
 
         info!(
             "Configuring GoXLR{}, Profile: {}, Mic Profile: {}",
-            device_type, profile_name, mic_name
+            device_type,
+            profile.name(),
+            mic_profile.name()
         );
 
+        let mut audio_handler = None;
+        if hardware.device_type == DeviceType::Full {
+            let audio_buffer = settings_handle.get_device_sampler_pre_buffer(&serial).await;
+            let audio_loader = AudioHandler::new(audio_buffer);
+            debug!("Created Audio Handler..");
+            debug!("{:?}", audio_loader);
+
+            if let Err(e) = &audio_loader {
+                error!("Error Running Script: {}", e);
+            }
+
+            if let Ok(audio) = audio_loader {
+                debug!("Audio Handler Loaded OK..");
+                audio_handler.replace(audio);
+            }
+        } else {
+            debug!("Not Spawning Audio Handler, Device is Mini!");
+        }
+
+        let hold_time = settings_handle.get_device_hold_time(&serial).await;
+        let vc_mute_also_mute_cm = settings_handle
+            .get_device_chat_mute_mutes_mic_to_chat(&serial)
+            .await;
+        let panic_mutes_chat = settings_handle.get_device_panic_mutes_chat(&serial).await;
+        let voice_activity = settings_handle.get_device_voice_activity(&serial).await;
+        let ducking = settings_handle.get_device_ducking(&serial).await;
+        let push_to_talk = settings_handle.get_device_push_to_talk(&serial).await;
+        let button_bindings = settings_handle.get_device_button_bindings(&serial).await;
+
+        debug!("--- DEVICE INFO ---");
+        debug!("Serial: {:?}", &serial);
+        debug!("Firmware: {:?}", hardware.versions.firmware);
+        debug!("DICE: {:?}", hardware.versions.dice);
+        debug!("Type: {:?}", hardware.device_type);
+        debug!("-------------------");
+
+        let mut device = Self {
+            profile,
+            mic_profile,
+            goxlr,
+            hardware,
+            hold_time: Duration::from_millis(hold_time.into()),
+            vc_mute_also_mute_cm,
+            panic_mutes_chat,
+            panic_active,
+            last_buttons: EnumSet::empty(),
+            physical_buttons: EnumSet::empty(),
+            virtual_buttons: EnumSet::empty(),
+            virtual_holds: EnumSet::empty(),
+            button_states: EnumMap::default(),
+            pending_clicks: EnumMap::default(),
+            button_bindings,
+            encoder_states: EnumMap::default(),
+            fader_last_seen: EnumMap::default(),
+            fader_pause_until: EnumMap::default(),
+            audio_handler,
+            settings: settings_handle,
+            global_events,
+
+            last_sample_error: None,
+            tap_tempo: VecDeque::with_capacity(4),
+            voice_activity: VoiceActivityDetector::new(voice_activity),
+            ducking: DuckingEngine::new(ducking),
+            push_to_talk: PushToTalkEngine::new(push_to_talk),
+            ducked_volumes: EnumMap::default(),
+            history: History::default(),
+            scene_fade: None,
+            volume_ramps: EnumMap::default(),
+            submix_ramps: EnumMap::default(),
+            fader_mute_timers: EnumMap::default(),
+            cough_mute_timer: None,
+            rule_state: None,
+            profile_loads: 0,
+            active_app_rules: vec![],
+            link_state: None,
+            link_commands: vec![],
+        };
+
+        device.apply_profile(None).await?;
+        device.apply_mic_profile().await?;
+
+        // Start tracking from the loaded state, so loading it isn't passed on to linked devices
+        device.link_state = Some(device.link_state());
+        Ok(device)
+    }
+
+    fn recover_profiles(state: &RecoverableState) -> Result<(ProfileAdapter, MicProfileAdapter)> {
+        let snapshot = &state.snapshot;
+        let name = snapshot.profile_name.clone();
+        let profile = ProfileAdapter::from_snapshot(name, &snapshot.profile)?;
+
+        let name = snapshot.mic_profile_name.clone();
+        let mic_profile = MicProfileAdapter::from_snapshot(name, &snapshot.mic_profile)?;
+        Ok((profile, mic_profile))
+    }
+
+    async fn load_profile(settings_handle: &SettingsHandle, serial: &str) -> ProfileAdapter {
+        let profile_name = settings_handle.get_device_profile_name(serial).await;
+        let profile_name = profile_name.unwrap_or_else(|| DEFAULT_PROFILE_NAME.to_string());
+
         let profile_path = settings_handle.get_profile_directory().await;
         let backup_path = settings_handle.get_backup_directory().await;
         let profile = ProfileAdapter::from_named(profile_name.clone(), &profile_path);
 
         // Check load situation..
-        let profile = match profile {
+        match profile {
             Ok(mut profile) => {
                 debug!("Profile Successfully Loaded, Performing Backup..");
                 profile.save(&backup_path, true).unwrap_or_else(|e| {
@@ -145,12 +323,18 @@ impl<'a> Device<'a> {
                     }
                 }
             }
-        };
+        }
+    }
 
+    async fn load_mic_profile(settings_handle: &SettingsHandle, serial: &str) -> MicProfileAdapter {
+        let mic_name = settings_handle.get_device_mic_profile_name(serial).await;
+        let mic_name = mic_name.unwrap_or_else(|| DEFAULT_MIC_PROFILE_NAME.to_string());
+
+        let backup_path = settings_handle.get_backup_directory().await;
         let mic_path = settings_handle.get_mic_profile_directory().await;
         let mic_profile = MicProfileAdapter::from_named(mic_name.clone(), &mic_path);
 
-        let mic_profile = match mic_profile {
+        match mic_profile {
             Ok(mut profile) => {
                 debug!("Mic Profile Successfully Loaded, Performing Backup..");
                 profile.save(&backup_path, true).unwrap_or_else(|e| {
@@ -177,63 +361,7 @@ impl<'a> Device<'a> {
                     }
                 }
             }
-        };
-
-        let mut audio_handler = None;
-        if hardware.device_type == DeviceType::Full {
-            let audio_buffer = settings_handle.get_device_sampler_pre_buffer(&serial).await;
-            let audio_loader = AudioHandler::new(audio_buffer);
-            debug!("Created Audio Handler..");
-            debug!("{:?}", audio_loader);
-
-            if let Err(e) = &audio_loader {
-                error!("Error Running Script: {}", e);
-            }
-
-            if let Ok(audio) = audio_loader {
-                debug!("Audio Handler Loaded OK..");
-                audio_handler.replace(audio);
-            }
-        } else {
-            debug!("Not Spawning Audio Handler, Device is Mini!");
         }
-
-        let hold_time = settings_handle.get_device_hold_time(&serial).await;
-        let vc_mute_also_mute_cm = settings_handle
-            .get_device_chat_mute_mutes_mic_to_chat(&serial)
-            .await;
-
-        debug!("--- DEVICE INFO ---");
-        debug!("Serial: {:?}", &serial);
-        debug!("Firmware: {:?}", hardware.versions.firmware);
-        debug!("DICE: {:?}", hardware.versions.dice);
-        debug!("Type: {:?}", hardware.device_type);
-        debug!("-------------------");
-
-        let mut device = Self {
-            profile,
-            mic_profile,
-            goxlr,
-            hardware,
-            hold_time: Duration::from_millis(hold_time.into()),
-            vc_mute_also_mute_cm,
-            last_buttons: EnumSet::empty(),
-            button_states: EnumMap::default(),
-            encoder_states: EnumMap::default(),
-            fader_last_seen: EnumMap::default(),
-            fader_pause_until: EnumMap::default(),
-            audio_handler,
-            settings: settings_handle,
-            global_events,
-
-            last_sample_error: None,
-            tap_tempo: VecDeque::with_capacity(4),
-        };
-
-        device.apply_profile(None).await?;
-        device.apply_mic_profile().await?;
-
-        Ok(device)
     }
 
     pub fn serial(&self) -> &str {
@@ -246,6 +374,12 @@ impl<'a> Device<'a> {
             fader_map[name] = self.get_fader_state(name);
         }
 
+        let mut cough_button = self.profile.get_cough_status();
+        cough_button.mute_timer = self
+            .cough_mute_timer
+            .as_ref()
+            .map(MuteTimer::remaining_secs);
+
         let mut button_states: EnumMap<Button, bool> = Default::default();
         for (button, state) in self.button_states.iter() {
             if state.press_time.is_some() {
@@ -267,6 +401,13 @@ impl<'a> Device<'a> {
 
         let wake_commands = self.settings.get_device_wake_commands(self.serial()).await;
 
+        let lock_commands = self.settings.get_device_lock_commands(self.serial()).await;
+
+        let unlock_commands = self
+            .settings
+            .get_device_unlock_commands(self.serial())
+            .await;
+
         let sampler_prerecord = self
             .settings
             .get_device_sampler_pre_buffer(self.serial())
@@ -310,12 +451,16 @@ impl<'a> Device<'a> {
             shutdown_commands,
             sleep_commands,
             wake_commands,
+            lock_commands,
+            unlock_commands,
+            button_bindings: self.button_bindings.clone(),
             fader_status: fader_map,
-            cough_button: self.profile.get_cough_status(),
+            cough_button,
             levels: Levels {
                 submix_supported: self.device_supports_submixes(),
                 output_monitor: self.profile.get_monitoring_mix(),
                 volumes,
+                mutes: self.profile.get_channel_mutes_ipc(),
                 submix: self.profile.get_submixes_ipc(submix_supported),
                 bleep: self.mic_profile.bleep_level(),
                 deess: self.mic_profile.get_deesser(),
@@ -351,93 +496,1077 @@ impl<'a> Device<'a> {
                 },
                 mute_hold_duration: self.hold_time.as_millis() as u16,
                 vc_mute_also_mute_cm: self.vc_mute_also_mute_cm,
+                panic_mutes_chat: self.panic_mutes_chat,
                 enable_monitor_with_fx: monitor_with_fx,
                 reset_sampler_on_clear: sampler_reset_on_clear,
                 lock_faders: locked_faders,
                 fade_duration: sampler_fade_duration,
                 vod_mode,
+                voice_activity: self.voice_activity.config(),
+                ducking: self.ducking.config(),
+                push_to_talk: self.push_to_talk.config(),
+                scenes: self.settings.get_device_scenes(self.serial()).await,
+                rules: self.settings.get_device_rules(self.serial()).await,
+                app_rules: self.settings.get_device_app_rules(self.serial()).await,
             },
             button_down: button_states,
             profile_name: self.profile.name().to_owned(),
             mic_profile_name: self.mic_profile.name().to_owned(),
+            speaking: self.voice_activity.is_speaking(),
+            ducked: self.ducking.is_ducked(),
+            panic: self.panic_active,
+            history: self.history.status(),
+        }
+    }
+
+    pub async fn shutdown(&mut self, avoid_save: bool) {
+        debug!("Shutting Down Device: {}", self.hardware.serial_number);
+
+        let commands = self
+            .settings
+            .get_device_shutdown_commands(&self.hardware.serial_number)
+            .await;
+
+        self.execute_command_list(commands, avoid_save).await;
+    }
+
+    pub async fn sleep(&mut self) {
+        debug!("Sleeping...");
+
+        let commands = self
+            .settings
+            .get_device_sleep_commands(&self.hardware.serial_number)
+            .await;
+
+        self.execute_command_list(commands, false).await;
+    }
+
+    pub async fn wake(&mut self) {
+        debug!("Waking...");
+
+        let commands = self
+            .settings
+            .get_device_wake_commands(&self.hardware.serial_number)
+            .await;
+
+        self.execute_command_list(commands, false).await;
+    }
+
+    pub async fn lock(&mut self) {
+        debug!("Locking...");
+
+        let commands = self
+            .settings
+            .get_device_lock_commands(&self.hardware.serial_number)
+            .await;
+
+        self.execute_command_list(commands, false).await;
+    }
+
+    pub async fn unlock(&mut self) {
+        debug!("Unlocking...");
+
+        let commands = self
+            .settings
+            .get_device_unlock_commands(&self.hardware.serial_number)
+            .await;
+
+        self.execute_command_list(commands, false).await;
+    }
+
+    // Compares the device state against the last check, and runs any rules which have been
+    // triggered. Daemon commands can't be run from here, so they're handed back to the caller.
+    pub async fn evaluate_rules(&mut self) -> Vec<DaemonCommand> {
+        let current = self.rule_state();
+        let previous = self.rule_state.take();
+        if previous.as_ref() == Some(&current) {
+            self.rule_state = previous;
+            return vec![];
+        }
+
+        let mut daemon_commands = vec![];
+        let rules = self.settings.get_device_rules(self.serial()).await;
+        for (name, rule) in rules {
+            if rule.enabled && is_triggered(&rule.trigger, previous.as_ref(), &current) {
+                debug!("Rule Triggered: {}", name);
+                self.execute_command_list(rule.commands, false).await;
+                daemon_commands.extend(rule.daemon_commands);
+            }
+        }
+
+        // Take the state again, so changes made by the rules don't trigger anything next time
+        self.rule_state = Some(self.rule_state());
+        daemon_commands
+    }
+
+    // Applies or reverts App Rules based on what's running, returns true if anything changed
+    pub async fn update_app_rules(&mut self, watcher: &mut AppWatcher) -> bool {
+        let rules = self.settings.get_device_app_rules(self.serial()).await;
+        if rules.is_empty() && self.active_app_rules.is_empty() {
+            return false;
+        }
+
+        // Revert anything where the app has closed, or the rule is no longer active
+        let ended: Vec<String> = self
+            .active_app_rules
+            .iter()
+            .filter(|active| {
+                !rules
+                    .get(&active.name)
+                    .is_some_and(|rule| rule.enabled && watcher.is_running(&rule.process))
+            })
+            .map(|active| active.name.clone())
+            .collect();
+
+        let mut changed = false;
+        for name in ended {
+            debug!("App Rule Ended: {}", name);
+            let profile = self.profile.name();
+            let mic_profile = self.mic_profile.name();
+            let switched = switched_by_hand(&self.active_app_rules, profile, mic_profile);
+
+            let restore = deactivate(&mut self.active_app_rules, &name);
+            if switched {
+                debug!("Profile changed while {} was running, not restoring", name);
+            } else {
+                self.restore_app_state(restore).await;
+            }
+            changed = true;
+        }
+
+        for (name, rule) in rules {
+            if !rule.enabled
+                || self
+                    .active_app_rules
+                    .iter()
+                    .any(|active| active.name == name)
+                || !watcher.is_running(&rule.process)
+            {
+                continue;
+            }
+
+            debug!("App Rule Started: {}", name);
+            let mut snapshot = None;
+            if rule.profile.is_some() || rule.mic_profile.is_some() {
+                match self.snapshot() {
+                    Ok(current) => snapshot = Some(current),
+                    Err(error) => {
+                        warn!("Unable to Store Profiles for App Rule {}: {}", name, error);
+                        continue;
+                    }
+                }
+            }
+
+            let restore = AppRestore {
+                profile: rule.profile.as_ref().and(snapshot.clone()),
+                mic_profile: rule.mic_profile.as_ref().and(snapshot),
+                scene: rule.scene.as_ref().map(|_| self.capture_scene()),
+            };
+            self.active_app_rules.push(ActiveAppRule {
+                name,
+                restore,
+                profile: rule.profile.clone(),
+                mic_profile: rule.mic_profile.clone(),
+            });
+
+            let mut commands = vec![];
+            if let Some(profile) = rule.profile {
+                commands.push(GoXLRCommand::LoadProfile(profile, false));
+            }
+            if let Some(mic_profile) = rule.mic_profile {
+                commands.push(GoXLRCommand::LoadMicProfile(mic_profile, false));
+            }
+            if let Some(scene) = rule.scene {
+                commands.push(GoXLRCommand::RecallScene(scene, 0));
+            }
+            self.execute_command_list(commands, false).await;
+            changed = true;
+        }
+        changed
+    }
+
+    async fn restore_app_state(&mut self, restore: AppRestore) {
+        if restore.profile.is_some() || restore.mic_profile.is_some() {
+            match self
+                .restore_profiles(restore.profile, restore.mic_profile)
+                .await
+            {
+                // These replace the profile, so the history no longer applies
+                Ok(()) => self.history.clear(),
+                Err(error) => warn!("Unable to Restore Profiles: {}", error),
+            }
+        }
+
+        // The scene goes last, so it's restored on top of the profile
+        if let Some(scene) = restore.scene
+            && let Err(error) = self.recall_scene(scene, Duration::ZERO).await
+        {
+            warn!("Unable to Restore Scene: {}", error);
+        }
+
+        // This is specific to this device, so shouldn't be passed on to linked ones
+        self.link_state = Some(self.link_state());
+    }
+
+    // Puts back the profile and / or mic profile from their snapshots, leaving the other alone
+    async fn restore_profiles(
+        &mut self,
+        profile: Option<ProfileSnapshot>,
+        mic_profile: Option<ProfileSnapshot>,
+    ) -> Result<()> {
+        if let Some(snapshot) = profile {
+            let profile = ProfileAdapter::from_snapshot(snapshot.profile_name, &snapshot.profile)?;
+            self.stop_all_samples(true, true).await?;
+
+            let volumes = self.profile.get_current_state();
+            self.profile = profile;
+            self.clear_mute_timers();
+            self.apply_profile(Some(volumes)).await?;
+            self.profile_loads = self.profile_loads.wrapping_add(1);
+        }
+
+        if let Some(snapshot) = mic_profile {
+            let name = snapshot.mic_profile_name;
+            self.mic_profile = MicProfileAdapter::from_snapshot(name, &snapshot.mic_profile)?;
+            self.apply_mic_profile().await?;
+        }
+        Ok(())
+    }
+
+    fn rule_state(&self) -> RuleState {
+        let mut mute_states =
+            EnumMap::from_fn(|channel| match self.profile.get_channel_mute(channel) {
+                Some(MuteFunction::All) => MuteState::MutedToAll,
+                Some(_) => MuteState::MutedToX,
+                None => MuteState::Unmuted,
+            });
+        for fader in FaderName::iter() {
+            let channel = self.profile.get_fader_assignment(fader);
+            let fader_state = self.profile.get_ipc_mute_state(fader);
+            mute_states[channel] = most_muted(mute_states[channel], fader_state);
+        }
+        let cough_state = self.profile.get_cough_status().state;
+        mute_states[ChannelName::Mic] = most_muted(mute_states[ChannelName::Mic], cough_state);
+
+        RuleState {
+            profile_name: self.profile.name().to_owned(),
+            profile_loads: self.profile_loads,
+            mute_states,
+            volumes: EnumMap::from_fn(|channel| self.profile.get_channel_volume(channel)),
+            samples_playing: EnumMap::from_fn(|bank| {
+                EnumMap::from_fn(|button| {
+                    self.audio_handler
+                        .as_ref()
+                        .is_some_and(|audio| audio.is_sample_playing(bank, button))
+                })
+            }),
+            effects: self.effect_states(),
+        }
+    }
+
+    fn effect_states(&self) -> EnumMap<RuleEffect, bool> {
+        let mini = self.is_device_mini();
+        EnumMap::from_fn(|effect| {
+            !mini
+                && match effect {
+                    RuleEffect::Fx => self.profile.is_fx_enabled(),
+                    RuleEffect::Megaphone => self.profile.is_megaphone_enabled(false),
+                    RuleEffect::Robot => self.profile.is_robot_enabled(false),
+                    RuleEffect::HardTune => self.profile.is_hardtune_enabled(false),
+                }
+        })
+    }
+
+    // Returns everything which has changed since the last call that linked devices may want
+    pub fn take_link_changes(&mut self) -> Vec<(LinkCategory, LinkChange)> {
+        let current = self.link_state();
+        let mut changes = match &self.link_state {
+            Some(previous) => previous.changes(&current),
+            None => vec![],
+        };
+        self.link_state = Some(current);
+
+        changes.append(&mut self.link_commands);
+        changes
+    }
+
+    // Applies changes from a linked device, without passing them on again
+    pub async fn apply_linked_changes(&mut self, changes: Vec<LinkChange>) {
+        for change in changes {
+            let command = change.command_for(|c| self.profile.get_fader_from_channel(c));
+            if let Err(error) = self.perform_command(command).await {
+                warn!("Unable to apply Linked Change: {}", error);
+            }
+        }
+        self.link_commands.clear();
+        self.link_state = Some(self.link_state());
+    }
+
+    fn link_state(&self) -> LinkState {
+        LinkState {
+            volumes: self.profile.get_channel_volume_map(),
+            fader_mutes: EnumMap::from_fn(|channel| {
+                let fader = self.profile.get_fader_from_channel(channel)?;
+                let state = self.profile.get_ipc_mute_state(fader);
+                Some((state, self.profile.get_mute_button_behaviour(fader)))
+            }),
+            channel_mutes: self.profile.get_channel_mutes_ipc(),
+            cough_mute: self.profile.get_cough_status().state,
+            effect_preset: self.profile.get_active_effect_bank(),
+            effects: self.effect_states(),
+        }
+    }
+
+    async fn execute_command_list(&mut self, commands: Vec<GoXLRCommand>, avoid_write: bool) {
+        for command in commands {
+            debug!("{:?}", command);
+
+            // If a command writes to the disk and we've been asked not to, we do nothing.
+            if avoid_write && Self::writes_to_disk(&command) {
+                warn!("Unable to Execute, command writes to the disk.");
+                continue;
+            }
+            let _ = self.perform_command(command).await;
+        }
+
+        // These run just before a sleep or shutdown, so don't leave anything queued.
+        if let Err(error) = self.goxlr.flush_writes(true) {
+            warn!("Unable to flush USB writes: {}", error);
+        }
+    }
+
+    // Below is a list of all commands which will write to a disk.
+    fn writes_to_disk(command: &GoXLRCommand) -> bool {
+        matches!(
+            command,
+            // Shutdown / Sleep / Wake / Lock Commandsets
+            GoXLRCommand::SetShutdownCommands(_)
+            | GoXLRCommand::SetSleepCommands(_)
+            | GoXLRCommand::SetWakeCommands(_)
+            | GoXLRCommand::SetLockCommands(_)
+            | GoXLRCommand::SetUnlockCommands(_)
+            | GoXLRCommand::SetButtonHoldCommands(_, _)
+            | GoXLRCommand::SetButtonDoublePressCommands(_, _)
+            | GoXLRCommand::SetButtonReleaseCommands(_, _)
+            // Presets
+            | GoXLRCommand::SaveActivePreset()
+            // Profile Related Commands
+            | GoXLRCommand::NewProfile(_)
+            | GoXLRCommand::LoadProfile(_, true)
+            | GoXLRCommand::SaveProfile()
+            | GoXLRCommand::SaveProfileAs(_)
+            // Mic Profile Related Commands
+            | GoXLRCommand::NewMicProfile(_)
+            | GoXLRCommand::LoadMicProfile(_, true)
+            | GoXLRCommand::SaveMicProfile()
+            | GoXLRCommand::SaveMicProfileAs(_)
+            // settings.json variables
+            | GoXLRCommand::SetSamplerPreBufferDuration(_)
+            | GoXLRCommand::SetVCMuteAlsoMuteCM(_)
+            | GoXLRCommand::SetPanicMutesChat(_)
+            | GoXLRCommand::SetMonitorWithFx(_)
+            | GoXLRCommand::SetSamplerResetOnClear(_)
+            | GoXLRCommand::SetLockFaders(_)
+            | GoXLRCommand::SetVoiceActivityEnabled(_)
+            | GoXLRCommand::SetVoiceActivityThreshold(_)
+            | GoXLRCommand::SetVoiceActivityAttack(_)
+            | GoXLRCommand::SetVoiceActivityHold(_)
+            | GoXLRCommand::SetDuckingEnabled(_)
+            | GoXLRCommand::SetDuckingSource(_)
+            | GoXLRCommand::SetDuckingInput(_)
+            | GoXLRCommand::SetDuckingTarget(_, _)
+            | GoXLRCommand::SetDuckingAmount(_)
+            | GoXLRCommand::SetDuckingAttack(_)
+            | GoXLRCommand::SetDuckingRelease(_)
+            | GoXLRCommand::SetPushToTalkEnabled(_)
+            | GoXLRCommand::SetPushToTalkReleaseTail(_)
+            | GoXLRCommand::SetPushToTalkLatch(_)
+            // Scenes
+            | GoXLRCommand::SaveScene(_)
+            | GoXLRCommand::DeleteScene(_)
+            // Rules
+            | GoXLRCommand::SetRule(_, _)
+            | GoXLRCommand::SetRuleEnabled(_, _)
+            | GoXLRCommand::DeleteRule(_)
+        )
+    }
+
+    // Changes to the profile configuration which can be undone. Live state (volumes, mutes, the
+    // sampler, effect toggles, etc) isn't kept in the history, so only commands listed here are.
+    fn is_undoable(command: &GoXLRCommand) -> bool {
+        matches!(
+            command,
+            GoXLRCommand::SetFader(_, _)
+                | GoXLRCommand::SetFaderMuteFunction(_, _)
+                | GoXLRCommand::SetFaderMuteTargets(_, _)
+                | GoXLRCommand::SetMicrophoneType(_)
+                | GoXLRCommand::SetMicrophoneGain(_, _)
+                | GoXLRCommand::SetRouter(_, _, _)
+                // Cough and Bleep Buttons
+                | GoXLRCommand::SetCoughMuteFunction(_)
+                | GoXLRCommand::SetCoughMuteTargets(_)
+                | GoXLRCommand::SetCoughIsHold(_)
+                | GoXLRCommand::SetSwearButtonVolume(_)
+                // Mic Processing
+                | GoXLRCommand::SetEqMiniGain(_, _)
+                | GoXLRCommand::SetEqMiniFreq(_, _)
+                | GoXLRCommand::SetEqGain(_, _)
+                | GoXLRCommand::SetEqFreq(_, _)
+                | GoXLRCommand::SetGateThreshold(_)
+                | GoXLRCommand::SetGateAttenuation(_)
+                | GoXLRCommand::SetGateAttack(_)
+                | GoXLRCommand::SetGateRelease(_)
+                | GoXLRCommand::SetGateActive(_)
+                | GoXLRCommand::SetCompressorThreshold(_)
+                | GoXLRCommand::SetCompressorRatio(_)
+                | GoXLRCommand::SetCompressorAttack(_)
+                | GoXLRCommand::SetCompressorReleaseTime(_)
+                | GoXLRCommand::SetCompressorMakeupGain(_)
+                | GoXLRCommand::SetElementDisplayMode(_, _)
+                | GoXLRCommand::SetDeeser(_)
+                // Lighting
+                | GoXLRCommand::SetAnimationMode(_)
+                | GoXLRCommand::SetAnimationMod1(_)
+                | GoXLRCommand::SetAnimationMod2(_)
+                | GoXLRCommand::SetAnimationWaterfall(_)
+                | GoXLRCommand::SetGlobalColour(_)
+                | GoXLRCommand::SetFaderDisplayStyle(_, _)
+                | GoXLRCommand::SetFaderColours(_, _, _)
+                | GoXLRCommand::SetAllFaderColours(_, _)
+                | GoXLRCommand::SetAllFaderDisplayStyle(_)
+                | GoXLRCommand::SetButtonColours(_, _, _)
+                | GoXLRCommand::SetButtonOffStyle(_, _)
+                | GoXLRCommand::SetButtonGroupColours(_, _, _)
+                | GoXLRCommand::SetButtonGroupOffStyle(_, _)
+                | GoXLRCommand::SetSimpleColour(_, _)
+                | GoXLRCommand::SetEncoderColour(_, _, _, _)
+                | GoXLRCommand::SetSampleColour(_, _, _, _)
+                | GoXLRCommand::SetSampleOffStyle(_, _)
+                | GoXLRCommand::LoadProfileColours(_)
+                // Effects
+                | GoXLRCommand::LoadEffectPreset(_)
+                | GoXLRCommand::RenameActivePreset(_)
+                | GoXLRCommand::SetReverbStyle(_)
+                | GoXLRCommand::SetReverbAmount(_)
+                | GoXLRCommand::SetReverbDecay(_)
+                | GoXLRCommand::SetReverbEarlyLevel(_)
+                | GoXLRCommand::SetReverbTailLevel(_)
+                | GoXLRCommand::SetReverbPreDelay(_)
+                | GoXLRCommand::SetReverbLowColour(_)
+                | GoXLRCommand::SetReverbHighColour(_)
+                | GoXLRCommand::SetReverbHighFactor(_)
+                | GoXLRCommand::SetReverbDiffuse(_)
+                | GoXLRCommand::SetReverbModSpeed(_)
+                | GoXLRCommand::SetReverbModDepth(_)
+                | GoXLRCommand::SetEchoStyle(_)
+                | GoXLRCommand::SetEchoAmount(_)
+                | GoXLRCommand::SetEchoFeedback(_)
+                | GoXLRCommand::SetEchoTempo(_)
+                | GoXLRCommand::SetEchoDelayLeft(_)
+                | GoXLRCommand::SetEchoDelayRight(_)
+                | GoXLRCommand::SetEchoFeedbackLeft(_)
+                | GoXLRCommand::SetEchoFeedbackRight(_)
+                | GoXLRCommand::SetEchoFeedbackXFBLtoR(_)
+                | GoXLRCommand::SetEchoFeedbackXFBRtoL(_)
+                | GoXLRCommand::SetPitchStyle(_)
+                | GoXLRCommand::SetPitchAmount(_)
+                | GoXLRCommand::SetPitchCharacter(_)
+                | GoXLRCommand::SetGenderStyle(_)
+                | GoXLRCommand::SetGenderAmount(_)
+                | GoXLRCommand::SetMegaphoneStyle(_)
+                | GoXLRCommand::SetMegaphoneAmount(_)
+                | GoXLRCommand::SetMegaphonePostGain(_)
+                | GoXLRCommand::SetRobotStyle(_)
+                | GoXLRCommand::SetRobotGain(_, _)
+                | GoXLRCommand::SetRobotFreq(_, _)
+                | GoXLRCommand::SetRobotWidth(_, _)
+                | GoXLRCommand::SetRobotWaveform(_)
+                | GoXLRCommand::SetRobotPulseWidth(_)
+                | GoXLRCommand::SetRobotThreshold(_)
+                | GoXLRCommand::SetRobotDryMix(_)
+                | GoXLRCommand::SetHardTuneStyle(_)
+                | GoXLRCommand::SetHardTuneAmount(_)
+                | GoXLRCommand::SetHardTuneRate(_)
+                | GoXLRCommand::SetHardTuneWindow(_)
+                | GoXLRCommand::SetHardTuneSource(_)
+                // Sampler
+                | GoXLRCommand::SetSamplerFunction(_, _, _)
+                | GoXLRCommand::SetSamplerOrder(_, _, _)
+                | GoXLRCommand::SetSampleStartPercent(_, _, _, _)
+                | GoXLRCommand::SetSampleStopPercent(_, _, _, _)
+                | GoXLRCommand::RemoveSampleByIndex(_, _, _)
+                // Scribbles
+                | GoXLRCommand::SetScribbleIcon(_, _)
+                | GoXLRCommand::SetScribbleText(_, _)
+                | GoXLRCommand::SetScribbleNumber(_, _)
+                | GoXLRCommand::SetScribbleInvert(_, _)
+                // Submixes and Monitoring
+                | GoXLRCommand::SetSubMixEnabled(_)
+                | GoXLRCommand::SetSubMixLinked(_, _)
+                | GoXLRCommand::SetSubMixOutputMix(_, _)
+                | GoXLRCommand::SetMonitorMix(_)
+        )
+    }
+
+    // Commands which change state held outside of the profiles (timers, ramps, playback, etc),
+    // restoring a profile snapshot won't put these back.
+    fn changes_runtime_state(command: &GoXLRCommand) -> bool {
+        matches!(
+            command,
+            GoXLRCommand::RampVolume(_, _, _, _)
+                | GoXLRCommand::RampSubMixVolume(_, _, _, _)
+                | GoXLRCommand::PlaySampleByIndex(_, _, _)
+                | GoXLRCommand::PlayNextSample(_, _)
+                | GoXLRCommand::StopSamplePlayback(_, _)
+                | GoXLRCommand::SetDuckingTriggered(_)
+                | GoXLRCommand::SetPanicActive(_)
+                | GoXLRCommand::SetFaderMuteTimer(_, _, _)
+                | GoXLRCommand::SetCoughMuteTimer(_, _)
+                | GoXLRCommand::SetChannelMute(_, _)
+                | GoXLRCommand::TriggerButton(_, _)
+                | GoXLRCommand::RecallScene(_, _)
+        )
+    }
+
+    // These swap out the profile entirely, so any history no longer applies to it.
+    fn replaces_profile(command: &GoXLRCommand) -> bool {
+        matches!(
+            command,
+            GoXLRCommand::NewProfile(_)
+                | GoXLRCommand::LoadProfile(_, _)
+                | GoXLRCommand::NewMicProfile(_)
+                | GoXLRCommand::LoadMicProfile(_, _)
+        )
+    }
+
+    // The subset of those which swap out the main profile, which rules see as a profile load.
+    fn loads_profile(command: &GoXLRCommand) -> bool {
+        matches!(
+            command,
+            GoXLRCommand::NewProfile(_) | GoXLRCommand::LoadProfile(_, _)
+        )
+    }
+
+    fn snapshot(&mut self) -> Result<ProfileSnapshot> {
+        Ok(ProfileSnapshot {
+            profile_name: self.profile.name().to_owned(),
+            profile: self.profile.snapshot()?,
+            mic_profile_name: self.mic_profile.name().to_owned(),
+            mic_profile: self.mic_profile.snapshot()?,
+        })
+    }
+
+    async fn restore(&mut self, snapshot: &ProfileSnapshot, keep_live_state: bool) -> Result<()> {
+        let name = snapshot.profile_name.clone();
+        let mut profile = ProfileAdapter::from_snapshot(name, &snapshot.profile)?;
+
+        let name = snapshot.mic_profile_name.clone();
+        let mic_profile = MicProfileAdapter::from_snapshot(name, &snapshot.mic_profile)?;
+
+        // Volumes, mutes and the effect toggles aren't undoable, so carry them over as they are
+        if keep_live_state {
+            for channel in ChannelName::iter() {
+                profile.set_channel_volume(channel, self.profile.get_channel_volume(channel))?;
+            }
+            for (channel, function) in self.profile.get_channel_mutes_ipc() {
+                profile.set_channel_mute(channel, function);
+            }
+
+            for fader in FaderName::iter() {
+                let (muted_to_x, muted_to_all, _) = self.profile.get_mute_button_state(fader);
+                profile.set_mute_button_on(fader, muted_to_x);
+                profile.set_mute_button_blink(fader, muted_to_all);
+
+                let previous = self.profile.get_mute_button_previous_volume(fader);
+                profile.set_mute_previous_volume(fader, previous)?;
+            }
+            profile.set_mute_chat_button_on(self.profile.get_mute_chat_button_on());
+            profile.set_mute_chat_button_blink(self.profile.get_mute_chat_button_blink());
+
+            profile.set_effects(self.profile.is_fx_enabled());
+            profile.set_megaphone(self.profile.is_megaphone_enabled(true));
+            profile.set_robot(self.profile.is_robot_enabled(true));
+            profile.set_hardtune(self.profile.is_hardtune_enabled(true));
+        }
+
+        let volumes = self.profile.get_current_state();
+        self.profile = profile;
+        self.mic_profile = mic_profile;
+        self.apply_profile(Some(volumes)).await?;
+        self.apply_mic_profile().await
+    }
+
+    async fn undo(&mut self) -> Result<()> {
+        let Some(entry) = self.history.pop_undo() else {
+            bail!("There is nothing to Undo");
+        };
+
+        let current = self.snapshot()?;
+        if let Err(error) = self.restore(&entry.snapshot, true).await {
+            self.history.push_undo(entry);
+            return Err(error);
+        }
+        self.history.push_redo(HistoryEntry {
+            description: entry.description,
+            snapshot: current,
+        });
+        Ok(())
+    }
+
+    async fn redo(&mut self) -> Result<()> {
+        let Some(entry) = self.history.pop_redo() else {
+            bail!("There is nothing to Redo");
+        };
+
+        let current = self.snapshot()?;
+        if let Err(error) = self.restore(&entry.snapshot, true).await {
+            self.history.push_redo(entry);
+            return Err(error);
+        }
+        self.history.push_undo(HistoryEntry {
+            description: entry.description,
+            snapshot: current,
+        });
+        Ok(())
+    }
+
+    // Runs a list of commands as a single unit, if any of them fail the profiles are restored to
+    // how they were before the batch started, and reapplied to the device.
+    pub async fn perform_batch(&mut self, commands: Vec<GoXLRCommand>) -> Result<()> {
+        // Anything written to disk can't be rolled back, so refuse the batch before starting.
+        if let Some(command) = commands.iter().find(|c| Self::writes_to_disk(c)) {
+            bail!(
+                "{:?} writes to the disk, and can't be part of a batch",
+                command
+            );
+        }
+
+        // Likewise, the history can't be rolled back.
+        if let Some(command) = commands.iter().find(|c| {
+            matches!(
+                c,
+                GoXLRCommand::Undo() | GoXLRCommand::Redo() | GoXLRCommand::ClearHistory()
+            )
+        }) {
+            bail!("{:?} can't be part of a batch", command);
+        }
+
+        // Only the profiles are rolled back, so nothing which changes state held elsewhere.
+        if let Some(command) = commands.iter().find(|c| Self::changes_runtime_state(c)) {
+            bail!(
+                "{:?} changes state outside of the profile, and can't be part of a batch",
+                command
+            );
+        }
+
+        let snapshot = self.snapshot()?;
+        let count = commands.len();
+        let replaces_profile = commands.iter().any(Self::replaces_profile);
+        let loads_profile = commands.iter().any(Self::loads_profile);
+
+        let mut linked = vec![];
+        for (index, command) in commands.into_iter().enumerate() {
+            if let Err(error) = self.apply_command(command.clone()).await {
+                warn!("Batch failed at {:?}: {}, rolling back..", command, error);
+                self.restore(&snapshot, false).await?;
+                bail!("Command {} ({:?}) failed: {}", index + 1, command, error);
+            }
+            if let Some(category) = forwarded_category(&command) {
+                linked.push((category, LinkChange::Command(command)));
+            }
+        }
+
+        // Nothing was rolled back, so these can be passed on to linked devices
+        self.link_commands.append(&mut linked);
+        if loads_profile {
+            self.profile_loads = self.profile_loads.wrapping_add(1);
+        }
+
+        // The whole batch is undone as a single change.
+        if replaces_profile {
+            self.history.clear();
+            self.link_state = Some(self.link_state());
+        } else {
+            self.history.push_undo(HistoryEntry {
+                description: format!("Batch of {count} commands"),
+                snapshot,
+            });
+        }
+        Ok(())
+    }
+
+    fn bindings_for(&self, button: Button) -> ButtonBinding {
+        self.button_bindings
+            .get(&button)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    async fn save_button_bindings(&mut self) {
+        self.button_bindings
+            .retain(|_, binding| !binding.is_empty());
+        self.settings
+            .set_device_button_bindings(self.serial(), self.button_bindings.clone())
+            .await;
+        self.settings.save().await;
+    }
+
+    async fn set_voice_activity(&mut self, config: VoiceActivity) {
+        self.voice_activity.set_config(config);
+        self.settings
+            .set_device_voice_activity(self.serial(), config)
+            .await;
+        self.settings.save().await;
+    }
+
+    async fn set_ducking(&mut self, config: Ducking) {
+        self.ducking.set_config(config.clone());
+        self.settings
+            .set_device_ducking(self.serial(), config)
+            .await;
+        self.settings.save().await;
+    }
+
+    async fn set_push_to_talk(&mut self, config: PushToTalk) -> Result<()> {
+        let was_enabled = self.push_to_talk.is_enabled();
+        self.push_to_talk.set_config(config);
+        self.settings
+            .set_device_push_to_talk(self.serial(), config)
+            .await;
+        self.settings.save().await;
+
+        if was_enabled != config.enabled {
+            // Enabling closes the mic straight away, disabling opens it back up
+            self.push_to_talk.reset();
+            self.cough_mute_timer = None;
+            self.set_cough_muted(config.enabled).await?;
+            self.update_button_states()?;
+        }
+        Ok(())
+    }
+
+    // Sends the ducked (or restored) volume to any channel where it's changed since the last
+    // call. The profile volumes are never touched, so they always reflect the user's levels.
+    fn apply_ducking(&mut self) -> Result<()> {
+        for channel in ChannelName::iter() {
+            let volume = self.profile.get_channel_volume(channel);
+            let ducked = Some(self.ducking.volume_for(channel, volume)).filter(|v| *v != volume);
+
+            if ducked != self.ducked_volumes[channel] {
+                let target = ducked.unwrap_or(volume);
+                self.goxlr.set_volume(channel, target)?;
+                self.ducked_volumes[channel] = ducked;
+
+                // The motorised faders follow, so hold off on reading them until they arrive
+                if let Some(fader) = self.profile.get_fader_from_channel(channel) {
+                    self.fader_pause_until[fader].paused = true;
+                    self.fader_pause_until[fader].until = target;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Whether the channel used as the Input ducking source is currently active
+    fn is_input_active(&self, channel: ChannelName) -> bool {
+        if channel == ChannelName::Sample {
+            return self.audio_handler.as_ref().is_some_and(|audio| {
+                SampleBank::iter().any(|bank| {
+                    SampleButtons::iter().any(|button| audio.is_sample_playing(bank, button))
+                })
+            });
+        }
+
+        let fader_muted = self
+            .profile
+            .get_fader_from_channel(channel)
+            .is_some_and(|fader| self.profile.get_fader_mute_state(fader) == Muted);
+
+        !fader_muted
+            && !self.channel_muted_to_all(channel)
+            && self.profile.get_channel_volume(channel) > 0
+    }
+
+    fn submix_active(&self) -> bool {
+        self.device_supports_submixes() && self.profile.is_submix_enabled()
+    }
+
+    fn capture_scene(&self) -> Scene {
+        let mut mute_states = EnumMap::default();
+        for fader in FaderName::iter() {
+            let channel = self.profile.get_fader_assignment(fader);
+            mute_states[channel] = Some(self.profile.get_ipc_mute_state(fader));
+        }
+
+        Scene {
+            volumes: self.profile.get_channel_volume_map(),
+            submix: self
+                .submix_active()
+                .then(|| EnumMap::from_fn(|mix| self.profile.get_submix_volume(mix))),
+            mute_states,
+            channel_mutes: Some(self.profile.get_channel_mutes_ipc()),
+            routing: EnumMap::from_fn(|input| self.profile.get_router(input)),
+        }
+    }
+
+    // Routing and mute states can't be faded so they're applied straight away, the volumes
+    // are then moved from where they are now by the update tick.
+    async fn recall_scene(&mut self, scene: Scene, duration: Duration) -> Result<()> {
+        for input in BasicInputDevice::iter() {
+            let current = self.profile.get_router(input);
+            let mut changed = false;
+            for output in BasicOutputDevice::iter() {
+                let enabled = scene.routing[input][output];
+                if current[output] != enabled {
+                    self.profile.set_routing(input, output, enabled)?;
+                    changed = true;
+                }
+            }
+            if changed {
+                self.apply_routing(input).await?;
+            }
+        }
+
+        for fader in FaderName::iter() {
+            let channel = self.profile.get_fader_assignment(fader);
+            let Some(state) = scene.mute_states[channel] else {
+                continue;
+            };
+            if self.profile.get_ipc_mute_state(fader) == state {
+                continue;
+            }
+            match state {
+                MuteState::Unmuted => self.unmute_fader(fader).await?,
+                MuteState::MutedToX => self.mute_fader_to_x(fader).await?,
+                MuteState::MutedToAll => self.mute_fader_to_all(fader, true).await?,
+            }
         }
-    }
 
-    pub async fn shutdown(&mut self, avoid_save: bool) {
-        debug!("Shutting Down Device: {}", self.hardware.serial_number);
+        if let Some(channel_mutes) = scene.channel_mutes {
+            let current = self.profile.get_channel_mutes_ipc();
+            for (channel, function) in channel_mutes {
+                if current[channel] != function {
+                    self.set_channel_mute(channel, function).await?;
+                }
+            }
+        }
 
-        let commands = self
-            .settings
-            .get_device_shutdown_commands(&self.hardware.serial_number)
-            .await;
+        // The scene takes over every volume, so stop any ramps that are running
+        self.volume_ramps = EnumMap::default();
+        self.submix_ramps = EnumMap::default();
 
-        self.execute_command_list(commands, avoid_save).await;
-    }
+        let mut fade = SceneFade::new(duration);
+        for channel in ChannelName::iter() {
+            // Mute to All handles the volume itself..
+            if scene.mute_states[channel] == Some(MuteState::MutedToAll) {
+                continue;
+            }
+            let volume = self.profile.get_channel_volume(channel);
+            fade.channels
+                .push(VolumeFade::new(channel, volume, scene.volumes[channel]));
+        }
 
-    pub async fn sleep(&mut self) {
-        debug!("Sleeping...");
+        // Linked submixes follow their channel, so only the unlinked ones need fading
+        if let Some(submix) = scene.submix
+            && self.submix_active()
+        {
+            for mix in SubMixChannelName::iter() {
+                if !self.profile.submix_linked(mix) {
+                    let volume = self.profile.get_submix_volume(mix);
+                    fade.submixes
+                        .push(VolumeFade::new(mix, volume, submix[mix]));
+                }
+            }
+        }
 
-        let commands = self
-            .settings
-            .get_device_sleep_commands(&self.hardware.serial_number)
-            .await;
+        self.scene_fade = Some(fade);
+        Ok(())
+    }
 
-        self.execute_command_list(commands, false).await;
+    // Stops anything that's currently moving this channel's volume.
+    fn cancel_volume_changes(&mut self, channel: ChannelName) {
+        self.volume_ramps[channel] = None;
+        self.submix_ramps[channel] = None;
+        if let Some(fade) = &mut self.scene_fade {
+            fade.channels.retain(|fade| fade.target != channel);
+        }
     }
 
-    pub async fn wake(&mut self) {
-        debug!("Waking...");
+    // Timers are for mutes in the current profile, so don't carry over to a different one. Other
+    // profile changes (undo, rollback, etc) keep them, they're dropped if the mute has gone.
+    fn clear_mute_timers(&mut self) {
+        self.fader_mute_timers = EnumMap::default();
+        self.cough_mute_timer = None;
+    }
 
-        let commands = self
-            .settings
-            .get_device_wake_commands(&self.hardware.serial_number)
-            .await;
+    // Lifts any timed mutes which have run out, returns true if anything changed.
+    async fn update_mute_timers(&mut self) -> Result<bool> {
+        let mut changed = false;
 
-        self.execute_command_list(commands, false).await;
+        for fader in FaderName::iter() {
+            let Some(timer) = self.fader_mute_timers[fader].as_mut() else {
+                continue;
+            };
+
+            // If something else has unmuted the fader, the timer has nothing left to do
+            let (muted_to_x, muted_to_all, _) = self.profile.get_mute_button_state(fader);
+            if !muted_to_x && !muted_to_all {
+                self.fader_mute_timers[fader] = None;
+                changed = true;
+                continue;
+            }
+
+            if timer.is_expired() {
+                let announce = timer.announces();
+                self.fader_mute_timers[fader] = None;
+                self.unmute_fader(fader).await?;
+
+                if announce {
+                    let name = self.profile.get_fader_assignment(fader);
+                    let message = format!("{name} Unmuted");
+                    let _ = self.global_events.send(TTSMessage(message)).await;
+                }
+                changed = true;
+            } else if timer.check_warning() {
+                if timer.announces() {
+                    let name = self.profile.get_fader_assignment(fader);
+                    let seconds = timer.remaining_secs();
+                    let message = format!("{name} Unmuting in {seconds} seconds");
+                    let _ = self.global_events.send(TTSMessage(message)).await;
+                }
+                changed = true;
+            }
+        }
+
+        if let Some(timer) = self.cough_mute_timer.as_mut() {
+            let (_, muted_to_x, muted_to_all, _) = self.profile.get_mute_chat_button_state();
+            if !muted_to_x && !muted_to_all {
+                self.cough_mute_timer = None;
+                changed = true;
+            } else if timer.is_expired() {
+                let announce = timer.announces();
+                self.cough_mute_timer = None;
+                self.set_cough_mute_state(MuteState::Unmuted).await?;
+
+                if announce {
+                    let message = "Mic Unmuted".to_string();
+                    let _ = self.global_events.send(TTSMessage(message)).await;
+                }
+                changed = true;
+            } else if timer.check_warning() {
+                if timer.announces() {
+                    let seconds = timer.remaining_secs();
+                    let message = format!("Mic Unmuting in {seconds} seconds");
+                    let _ = self.global_events.send(TTSMessage(message)).await;
+                }
+                changed = true;
+            }
+        }
+
+        // Refresh the buttons, so any timers which are running out start flashing
+        if changed {
+            self.update_button_states()?;
+        }
+        Ok(changed)
     }
 
-    async fn execute_command_list(&mut self, commands: Vec<GoXLRCommand>, avoid_write: bool) {
-        for command in commands {
-            debug!("{:?}", command);
+    // Steps any running volume ramps, returns true if anything changed.
+    fn update_volume_ramps(&mut self) -> Result<bool> {
+        let mut changed = false;
+        for channel in ChannelName::iter() {
+            if let Some(ramp) = &self.volume_ramps[channel] {
+                let finished = ramp.is_finished();
+                let volume = ramp.volume();
+                if finished {
+                    self.volume_ramps[channel] = None;
+                }
+                if self.profile.get_channel_volume(channel) != volume {
+                    self.set_channel_volume(channel, volume)?;
+                    changed = true;
+                }
+            }
 
-            // Below is a list of all commands which will write to a disk, if any of them are
-            // in our command list, we do nothing.
-            match command {
-                // Shutdown / Sleep / Wake Commandsets
-                GoXLRCommand::SetShutdownCommands(_)
-                | GoXLRCommand::SetSleepCommands(_)
-                | GoXLRCommand::SetWakeCommands(_)
-                // Presets
-                | GoXLRCommand::SaveActivePreset()
-                // Profile Related Commands
-                | GoXLRCommand::NewProfile(_)
-                | GoXLRCommand::LoadProfile(_, true)
-                | GoXLRCommand::SaveProfile()
-                | GoXLRCommand::SaveProfileAs(_)
-                // Mic Profile Related Commands
-                | GoXLRCommand::NewMicProfile(_)
-                | GoXLRCommand::LoadMicProfile(_, true)
-                | GoXLRCommand::SaveMicProfile()
-                | GoXLRCommand::SaveMicProfileAs(_)
-                // settings.json variables
-                | GoXLRCommand::SetSamplerPreBufferDuration(_)
-                | GoXLRCommand::SetVCMuteAlsoMuteCM(_)
-                | GoXLRCommand::SetMonitorWithFx(_)
-                | GoXLRCommand::SetSamplerResetOnClear(_)
-                | GoXLRCommand::SetLockFaders(_)
-                => {
-                    if !avoid_write {
-                        let _ = self.perform_command(command).await;
-                    } else {
-                        warn!("Unable to Execute, command writes to the disk.");
-                    }
+            if let Some(ramp) = &self.submix_ramps[channel] {
+                let finished = ramp.is_finished();
+                let volume = ramp.volume();
+                if finished {
+                    self.submix_ramps[channel] = None;
+                }
+
+                // Submixing may have been turned off underneath us..
+                let Some(mix) = self.profile.get_submix_from_channel(channel) else {
+                    continue;
+                };
+                if self.submix_active() && self.profile.get_submix_volume(mix) != volume {
+                    self.apply_submix_volume(channel, volume)?;
+                    changed = true;
                 }
+            }
+        }
+        Ok(changed)
+    }
+
+    // Moves the volumes of a running scene fade along, returns true if anything changed.
+    fn update_scene_fade(&mut self) -> Result<bool> {
+        let Some(fade) = &self.scene_fade else {
+            return Ok(false);
+        };
 
-                _ => {
-                    let _ = self.perform_command(command).await;
+        let progress = fade.progress();
+        let channels: Vec<(ChannelName, u8)> = fade
+            .channels
+            .iter()
+            .map(|fade| (fade.target, fade.volume_at(progress)))
+            .collect();
+        let submixes: Vec<(SubMixChannelName, u8)> = fade
+            .submixes
+            .iter()
+            .map(|fade| (fade.target, fade.volume_at(progress)))
+            .collect();
+
+        let mut changed = false;
+        for (channel, volume) in channels {
+            if self.profile.get_channel_volume(channel) != volume {
+                self.set_channel_volume(channel, volume)?;
+                changed = true;
+
+                // Scenes are per device, so the fade shouldn't be copied to linked ones
+                if let Some(link_state) = &mut self.link_state {
+                    link_state.follow_volume(channel, volume);
                 }
             }
         }
+        for (mix, volume) in submixes {
+            if self.profile.get_submix_volume(mix) != volume {
+                self.profile.set_submix_volume(mix, volume);
+                self.goxlr.set_sub_volume(mix, volume)?;
+                changed = true;
+            }
+        }
+
+        if progress >= 1.0 {
+            self.scene_fade = None;
+        }
+        Ok(changed)
+    }
+
+    pub fn into_recoverable_state(mut self) -> Result<RecoverableState> {
+        Ok(RecoverableState {
+            snapshot: self.snapshot()?,
+            panic_active: self.panic_active,
+        })
+    }
+
+    pub fn is_connected(&mut self) -> bool {
+        self.goxlr.is_connected()
+    }
+
+    pub fn flush_writes(&mut self) -> Result<()> {
+        self.goxlr.flush_writes(false)
+    }
+
+    pub fn has_pending_writes(&self) -> bool {
+        self.goxlr.has_pending_writes()
     }
 
     pub fn profile(&self) -> &ProfileAdapter {
@@ -500,6 +1629,19 @@ impl<'a> Device<'a> {
             }
         }
 
+        // Any single presses which weren't followed up by a second can now be handled
+        for button in Buttons::iter() {
+            if let Some(time) = self.pending_clicks[button]
+                && time.elapsed() > DOUBLE_PRESS_WINDOW
+            {
+                self.pending_clicks[button] = None;
+                if let Err(error) = self.on_button_up(button, &ButtonState::default()).await {
+                    error!("{}", error);
+                }
+                state_updated = true;
+            }
+        }
+
         // Find any buttons that have been held, and action if needed.
         for button in self.last_buttons {
             if !self.button_states[button].hold_handled
@@ -513,6 +1655,64 @@ impl<'a> Device<'a> {
             }
         }
 
+        // Virtual holds let go of the button once the hold has been handled
+        let mut released = false;
+        for button in self.virtual_holds {
+            if self.button_states[button].hold_handled {
+                self.virtual_holds.remove(button);
+                self.virtual_buttons.remove(button);
+                released = true;
+            }
+        }
+        if released && self.update_buttons().await {
+            state_updated = true;
+        }
+
+        if self.update_scene_fade()? {
+            state_updated = true;
+        }
+        if self.update_volume_ramps()? {
+            state_updated = true;
+        }
+
+        if self.update_mute_timers().await? {
+            state_updated = true;
+        }
+
+        // Push to Talk release tail has expired, close the mic..
+        if self.push_to_talk.poll() {
+            self.set_cough_muted(true).await?;
+            self.update_button_states()?;
+            state_updated = true;
+        }
+
+        // Sample the Mic Level for Voice Activity Detection, if it's needed.
+        if self.voice_activity.is_enabled() || self.ducking.needs_mic_level() {
+            match self.get_mic_level().await {
+                Ok(level) => {
+                    if self.voice_activity.process(level) {
+                        state_updated = true;
+                    }
+                }
+                Err(error) => warn!("Unable to read Mic Level for Voice Activity: {}", error),
+            }
+        }
+
+        let active = match self.ducking.source() {
+            DuckingSource::Microphone => self.voice_activity.is_speaking(),
+            DuckingSource::Input => self.is_input_active(self.ducking.input()),
+            DuckingSource::Manual => self.ducking.is_triggered(),
+        };
+
+        // Keep applying while ducked, in case a fader has been moved underneath us..
+        let was_ducked = self.ducking.is_ducked();
+        if self.ducking.update(active) || was_ducked {
+            self.apply_ducking()?;
+            if was_ducked != self.ducking.is_ducked() {
+                state_updated = true;
+            }
+        }
+
         Ok(state_updated)
     }
 
@@ -525,12 +1725,26 @@ impl<'a> Device<'a> {
             changed = result;
         }
 
-        let pressed_buttons = state.pressed.difference(self.last_buttons);
+        self.physical_buttons = state.pressed;
+        if self.update_buttons().await {
+            changed = true;
+        }
+        Ok(changed)
+    }
+
+    // Works out which buttons have been pressed or released since we last looked, presses from
+    // the hardware and virtual presses over IPC both come through here so they behave the same.
+    async fn update_buttons(&mut self) -> bool {
+        let pressed = self.physical_buttons | self.virtual_buttons;
+        let mut changed = false;
+
+        let pressed_buttons = pressed.difference(self.last_buttons);
         for button in pressed_buttons {
             // This is a new press, store it in the states..
             self.button_states[button] = ButtonState {
                 press_time: Some(Instant::now()),
                 hold_handled: false,
+                binding_handled: false,
             };
 
             if let Err(error) = self.on_button_down(button).await {
@@ -540,25 +1754,73 @@ impl<'a> Device<'a> {
             changed = true;
         }
 
-        let released_buttons = self.last_buttons.difference(state.pressed);
+        let released_buttons = self.last_buttons.difference(pressed);
         for button in released_buttons {
             let button_state = self.button_states[button];
 
             // Output errors, but don't throw them up the stack!
-            if let Err(error) = self.on_button_up(button, &button_state).await {
+            if let Err(error) = self.on_button_release(button, &button_state).await {
                 error!("{}", error);
             }
 
-            self.button_states[button] = ButtonState {
-                press_time: None,
-                hold_handled: false,
-            };
+            self.button_states[button] = ButtonState::default();
 
             changed = true;
         }
 
-        self.last_buttons = state.pressed;
-        Ok(changed)
+        self.last_buttons = pressed;
+        changed
+    }
+
+    // Buttons which do their thing as soon as they're pressed, rather than on release.
+    fn acts_on_press(button: Button) -> bool {
+        matches!(
+            button,
+            Button::Cough
+                | Button::Bleep
+                | Button::SamplerTopLeft
+                | Button::SamplerTopRight
+                | Button::SamplerBottomLeft
+                | Button::SamplerBottomRight
+        )
+    }
+
+    // Handles any bindings on the button before passing the release on to on_button_up
+    async fn on_button_release(&mut self, button: Buttons, state: &ButtonState) -> Result<()> {
+        let standard = usb_to_standard_button(button);
+
+        if state.binding_handled {
+            let release = self.bindings_for(standard).release;
+            if !release.is_empty() {
+                debug!("Handling Bound Button Release: {:?}", button);
+                self.execute_command_list(release, false).await;
+                self.update_button_states()?;
+            }
+
+            // A bound hold replaces the release, unless the button needs it to finish what it
+            // started
+            if !Self::acts_on_press(standard) {
+                return Ok(());
+            }
+        }
+
+        let double_press = self.bindings_for(standard).double_press;
+        if state.hold_handled || double_press.is_empty() || Self::acts_on_press(standard) {
+            return self.on_button_up(button, state).await;
+        }
+
+        // If this is the second press, run the binding, otherwise hold the click back until we
+        // know a second press isn't coming.
+        if let Some(time) = self.pending_clicks[button].take()
+            && time.elapsed() <= DOUBLE_PRESS_WINDOW
+        {
+            debug!("Handling Button Double Press: {:?}", button);
+            self.execute_command_list(double_press, false).await;
+            self.update_button_states()?;
+            return Ok(());
+        }
+        self.pending_clicks[button] = Some(Instant::now());
+        Ok(())
     }
 
     async fn on_button_down(&mut self, button: Buttons) -> Result<()> {
@@ -566,7 +1828,12 @@ impl<'a> Device<'a> {
 
         match button {
             Buttons::MicrophoneMute => {
-                self.handle_cough_mute(true, false, false, false).await?;
+                if self.push_to_talk.is_enabled() {
+                    self.push_to_talk.press();
+                    self.set_cough_muted(false).await?;
+                } else {
+                    self.handle_cough_mute(true, false, false, false).await?;
+                }
             }
             Buttons::Bleep => {
                 self.handle_swear_button(true).await?;
@@ -596,6 +1863,19 @@ impl<'a> Device<'a> {
     async fn on_button_hold(&mut self, button: Buttons) -> Result<()> {
         debug!("Handling Button Hold: {:?}", button);
 
+        // Holding is how Push to Talk opens the mic, so there's nothing else to do
+        if button == Buttons::MicrophoneMute && self.push_to_talk.is_enabled() {
+            return Ok(());
+        }
+
+        let commands = self.bindings_for(usb_to_standard_button(button)).hold;
+        if !commands.is_empty() {
+            self.button_states[button].binding_handled = true;
+            self.execute_command_list(commands, false).await;
+            self.update_button_states()?;
+            return Ok(());
+        }
+
         // Fader mute buttons maintain their own state check, so it can be programmatically called.
         match button {
             Buttons::Fader1Mute => {
@@ -654,8 +1934,13 @@ impl<'a> Device<'a> {
                 }
             }
             Buttons::MicrophoneMute => {
-                self.handle_cough_mute(false, true, false, state.hold_handled)
-                    .await?;
+                if self.push_to_talk.is_enabled() {
+                    // The mic will be closed by update_state once the release tail has passed
+                    self.push_to_talk.release();
+                } else {
+                    self.handle_cough_mute(false, true, false, state.hold_handled)
+                        .await?;
+                }
             }
             Buttons::Bleep => {
                 self.handle_swear_button(false).await?;
@@ -809,39 +2094,130 @@ impl<'a> Device<'a> {
         Ok(())
     }
 
-    async fn handle_fader_mute(&mut self, fader: FaderName, held: bool) -> Result<()> {
-        // OK, so a fader button has been pressed, we need to determine behaviour, based on the colour map..
-        let (muted_to_x, muted_to_all, mute_function) = self.profile.get_mute_button_state(fader);
+    async fn handle_fader_mute(&mut self, fader: FaderName, held: bool) -> Result<()> {
+        // OK, so a fader button has been pressed, we need to determine behaviour, based on the colour map..
+        let (muted_to_x, muted_to_all, mute_function) = self.profile.get_mute_button_state(fader);
+
+        // Should we be muting this fader to all channels?
+        if held || (!muted_to_x && mute_function == MuteFunction::All) {
+            if held && muted_to_all {
+                // Holding the button when it's already muted to all does nothing.
+                return Ok(());
+            }
+            self.mute_fader_to_all(fader, held).await?;
+        }
+
+        // Button has been pressed, and we're already in some kind of muted state..
+        if !held && muted_to_x {
+            self.unmute_fader(fader).await?;
+        }
+
+        // Button has been pressed, we're not muted, and we need to transient mute..
+        if !held && !muted_to_x && mute_function != MuteFunction::All {
+            self.mute_fader_to_x(fader).await?;
+        }
+        Ok(())
+    }
+
+    async fn unmute_chat_if_muted(&mut self) -> Result<()> {
+        let (_mute_toggle, muted_to_x, muted_to_all, _mute_function) =
+            self.profile.get_mute_chat_button_state();
+
+        if muted_to_x || muted_to_all {
+            self.handle_cough_mute(true, false, false, false).await?;
+        }
+
+        Ok(())
+    }
+
+    // Panic pulls every input off the stream in one go. It's applied on top of the routing rather
+    // than through the profile, so turning it off puts everything back exactly as it was.
+    async fn set_panic(&mut self, active: bool) -> Result<()> {
+        if self.panic_active == active {
+            return Ok(());
+        }
+
+        self.panic_active = active;
+        for input in BasicInputDevice::iter() {
+            self.apply_routing(input).await?;
+        }
+        self.update_button_states()?;
+
+        let message = if active {
+            "Panic Enabled, Stream Muted"
+        } else {
+            "Panic Disabled"
+        };
+        let _ = self
+            .global_events
+            .send(TTSMessage(message.to_string()))
+            .await;
+        Ok(())
+    }
+
+    async fn set_cough_mute_state(&mut self, state: MuteState) -> Result<()> {
+        // This is more complicated because the 'state' of the mute can come from
+        // various different locations, so what we're going to do is simply update
+        // the profile, and re-apply the Mute settings from there.
+        if !self.profile.is_mute_chat_button_toggle() {
+            bail!("Cannot Set state when Mute button is in 'Hold' Mode");
+        }
+        if self.push_to_talk.is_enabled() {
+            bail!("Cannot Set state when Push to Talk is enabled");
+        }
+        match state {
+            MuteState::Unmuted => {
+                self.profile.set_mute_chat_button_on(false);
+                self.profile.set_mute_chat_button_blink(false);
+            }
+            MuteState::MutedToX => {
+                self.profile.set_mute_chat_button_on(true);
+                self.profile.set_mute_chat_button_blink(false);
+            }
+            MuteState::MutedToAll => {
+                self.profile.set_mute_chat_button_on(true);
+                self.profile.set_mute_chat_button_blink(true);
+            }
+        }
+        self.apply_cough_from_profile()?;
+        self.apply_effects(LinkedHashSet::from_iter([EffectKey::MicInputMute]))?;
+        self.apply_routing(BasicInputDevice::Microphone).await?;
+        self.update_button_states()?;
+        Ok(())
+    }
+
+    // Used by Push to Talk to open or close the mic, this goes via the cough button so the mic
+    // is muted to wherever the button is configured to mute it to.
+    async fn set_cough_muted(&mut self, muted: bool) -> Result<()> {
+        let (_mute_toggle, muted_to_x, muted_to_all, mute_function) =
+            self.profile.get_mute_chat_button_state();
 
-        // Should we be muting this fader to all channels?
-        if held || (!muted_to_x && mute_function == MuteFunction::All) {
-            if held && muted_to_all {
-                // Holding the button when it's already muted to all does nothing.
-                return Ok(());
-            }
-            self.mute_fader_to_all(fader, held).await?;
+        if muted == (muted_to_x || muted_to_all) {
+            return Ok(());
         }
 
-        // Button has been pressed, and we're already in some kind of muted state..
-        if !held && muted_to_x {
-            self.unmute_fader(fader).await?;
-        }
+        self.profile.set_mute_chat_button_on(muted);
+        self.profile.set_mute_chat_button_blink(false);
 
-        // Button has been pressed, we're not muted, and we need to transient mute..
-        if !held && !muted_to_x && mute_function != MuteFunction::All {
-            self.mute_fader_to_x(fader).await?;
+        if muted && mute_function == MuteFunction::All {
+            self.goxlr.set_channel_state(ChannelName::Mic, Muted)?;
+        } else if !muted
+            && (muted_to_all || mute_function == MuteFunction::All)
+            && !self.mic_muted_by_fader()
+        {
+            self.set_channel_unmuted(ChannelName::Mic)?;
         }
+
+        self.apply_effects(LinkedHashSet::from_iter([EffectKey::MicInputMute]))?;
+        self.apply_routing(BasicInputDevice::Microphone).await?;
         Ok(())
     }
 
-    async fn unmute_chat_if_muted(&mut self) -> Result<()> {
-        let (_mute_toggle, muted_to_x, muted_to_all, _mute_function) =
-            self.profile.get_mute_chat_button_state();
-
-        if muted_to_x || muted_to_all {
-            self.handle_cough_mute(true, false, false, false).await?;
+    // Brings the mic back in line with Push to Talk after the cough state has been changed
+    async fn apply_push_to_talk(&mut self) -> Result<()> {
+        if self.push_to_talk.is_enabled() {
+            self.set_cough_muted(!self.push_to_talk.is_open()).await?;
         }
-
         Ok(())
     }
 
@@ -917,7 +2293,7 @@ impl<'a> Device<'a> {
                     if (muted_to_all || (muted_to_x && mute_function == MuteFunction::All))
                         && !self.mic_muted_by_fader()
                     {
-                        self.goxlr.set_channel_state(ChannelName::Mic, Unmuted)?;
+                        self.set_channel_unmuted(ChannelName::Mic)?;
                         self.apply_effects(LinkedHashSet::from_iter([EffectKey::MicInputMute]))?;
                     }
 
@@ -945,7 +2321,7 @@ impl<'a> Device<'a> {
 
             self.profile.set_mute_chat_button_on(false);
             if mute_function == MuteFunction::All && !self.mic_muted_by_fader() {
-                self.goxlr.set_channel_state(ChannelName::Mic, Unmuted)?;
+                self.set_channel_unmuted(ChannelName::Mic)?;
                 self.apply_effects(LinkedHashSet::from_iter([EffectKey::MicInputMute]))?;
             }
 
@@ -1082,7 +2458,7 @@ impl<'a> Device<'a> {
             if channel != ChannelName::Mic
                 || (channel == ChannelName::Mic && !self.mic_muted_by_cough())
             {
-                self.goxlr.set_channel_state(channel, Unmuted)?;
+                self.set_channel_unmuted(channel)?;
                 self.apply_effects(LinkedHashSet::from_iter([EffectKey::MicInputMute]))?;
             }
 
@@ -1630,6 +3006,61 @@ impl<'a> Device<'a> {
         false
     }
 
+    fn channel_muted_to_all(&self, channel: ChannelName) -> bool {
+        self.profile.get_channel_mute(channel) == Some(MuteFunction::All)
+    }
+
+    // Unmutes a channel on the device, unless it's been muted to all independently of a fader
+    fn set_channel_unmuted(&mut self, channel: ChannelName) -> Result<()> {
+        if self.channel_muted_to_all(channel) {
+            return Ok(());
+        }
+        self.goxlr.set_channel_state(channel, Unmuted)
+    }
+
+    async fn set_channel_mute(
+        &mut self,
+        channel: ChannelName,
+        function: Option<BasicMuteFunction>,
+    ) -> Result<()> {
+        let input = self.get_basic_input_from_channel(channel);
+        if input.is_none() && function.is_some_and(|function| function != BasicMuteFunction::All) {
+            bail!("{} isn't routable, so can only be Muted to All", channel);
+        }
+
+        self.profile.set_channel_mute(channel, function);
+        if self.channel_muted_to_all(channel) {
+            self.goxlr.set_channel_state(channel, Muted)?;
+        } else {
+            // Only unmute if nothing else is holding the channel muted..
+            let muted = if channel == ChannelName::Mic {
+                self.mic_muted_by_fader() || self.mic_muted_by_cough()
+            } else if let Some(fader) = self.profile.get_fader_from_channel(channel) {
+                let (muted_to_x, muted_to_all, mute_function) =
+                    self.profile.get_mute_button_state(fader);
+                muted_to_all || (muted_to_x && mute_function == MuteFunction::All)
+            } else {
+                false
+            };
+            if !muted {
+                self.set_channel_unmuted(channel)?;
+            }
+        }
+
+        if channel == ChannelName::Mic {
+            self.apply_effects(LinkedHashSet::from_iter([EffectKey::MicInputMute]))?;
+        }
+
+        // Chat may be transiently muting the Chat Mic..
+        if let Some(input) = input {
+            self.apply_routing(input).await?;
+        }
+        if channel == ChannelName::Chat {
+            self.apply_routing(BasicInputDevice::Microphone).await?;
+        }
+        Ok(())
+    }
+
     fn mic_muted_by_cough(&self) -> bool {
         let (_mute_toggle, muted_to_x, muted_to_all, mute_function) =
             self.profile.get_mute_chat_button_state();
@@ -1642,6 +3073,19 @@ impl<'a> Device<'a> {
 
         for fader in FaderName::iter() {
             let new_volume = volumes[fader as usize];
+
+            // While ramping the motor is chasing a moving target, so rather than the latch we
+            // assume anything off the ramp's path is a human grabbing the fader.
+            let channel = self.profile.get_fader_assignment(fader);
+            if !self.is_device_mini()
+                && let Some(ramp) = &self.volume_ramps[channel]
+            {
+                if ramp.is_on_path(new_volume) {
+                    continue;
+                }
+                self.fader_pause_until[fader].paused = false;
+            }
+
             if self.is_device_mini() {
                 if new_volume == self.fader_last_seen[fader] {
                     continue;
@@ -1667,9 +3111,16 @@ impl<'a> Device<'a> {
                     self.fader_pause_until[fader].paused = false;
                 }
             }
+            // On the Full the fader follows any ducked volume, that's not a human moving it..
+            if !self.is_device_mini()
+                && let Some(ducked) = self.ducked_volumes[channel]
+                && new_volume.abs_diff(ducked) <= 5
+            {
+                self.fader_last_seen[fader] = new_volume;
+                continue;
+            }
             self.fader_last_seen[fader] = new_volume;
 
-            let channel = self.profile.get_fader_assignment(fader);
             let old_volume = self.profile.get_channel_volume(channel);
 
             if new_volume != old_volume {
@@ -1681,6 +3132,12 @@ impl<'a> Device<'a> {
                 value_changed = true;
                 self.profile.set_channel_volume(channel, new_volume)?;
 
+                // The fader has replaced any ducked volume on the device..
+                self.ducked_volumes[channel] = None;
+
+                // and a human moving the fader takes over from anything moving it for them
+                self.cancel_volume_changes(channel);
+
                 // Update the Submix..
                 self.update_submix_for(channel, new_volume)?;
             }
@@ -1688,6 +3145,23 @@ impl<'a> Device<'a> {
         Ok(value_changed)
     }
 
+    fn set_channel_volume(&mut self, channel: ChannelName, volume: u8) -> Result<()> {
+        self.goxlr.set_volume(channel, volume)?;
+        self.profile.set_channel_volume(channel, volume)?;
+
+        // Update the Submix when volume changes via IPC
+        self.update_submix_for(channel, volume)?;
+
+        if let Some(fader) = self.profile.get_fader_from_channel(channel) {
+            self.fader_pause_until[fader].paused = true;
+            self.fader_pause_until[fader].until = volume;
+        }
+
+        // Re-apply any ducking on top of the new volume
+        self.ducked_volumes[channel] = None;
+        self.apply_ducking()
+    }
+
     fn update_submix_for(&mut self, channel: ChannelName, volume: u8) -> Result<()> {
         if self.device_supports_submixes()
             && self.profile.is_submix_enabled()
@@ -1843,6 +3317,44 @@ impl<'a> Device<'a> {
     }
 
     pub async fn perform_command(&mut self, command: GoXLRCommand) -> Result<()> {
+        let linked = forwarded_category(&command)
+            .map(|category| (category, LinkChange::Command(command.clone())));
+
+        if Self::replaces_profile(&command) {
+            let loads_profile = Self::loads_profile(&command);
+            self.apply_command(command).await?;
+            self.history.clear();
+            if loads_profile {
+                self.profile_loads = self.profile_loads.wrapping_add(1);
+            }
+
+            // A different profile isn't a change to pass on, so start tracking from here
+            self.link_state = Some(self.link_state());
+        } else if matches!(command, GoXLRCommand::RecallScene(..)) {
+            // Likewise, scenes are saved per device, so shouldn't be copied to linked ones
+            self.apply_command(command).await?;
+            self.link_state = Some(self.link_state());
+        } else if !Self::is_undoable(&command) {
+            self.apply_command(command).await?;
+        } else {
+            // Dragging a slider sends a stream of commands, only snapshot at the start of it.
+            let snapshot = match self.history.is_continuation(&command) {
+                true => None,
+                false => Some(self.snapshot()?),
+            };
+
+            self.apply_command(command.clone()).await?;
+            self.history.record(&command, snapshot);
+        }
+
+        // Only pass this on to linked devices once we know it's worked
+        if let Some(linked) = linked {
+            self.link_commands.push(linked);
+        }
+        Ok(())
+    }
+
+    async fn apply_command(&mut self, command: GoXLRCommand) -> Result<()> {
         match command {
             GoXLRCommand::SetShutdownCommands(commands) => {
                 self.settings
@@ -1862,6 +3374,66 @@ impl<'a> Device<'a> {
                     .await;
                 self.settings.save().await;
             }
+            GoXLRCommand::SetLockCommands(commands) => {
+                self.settings
+                    .set_device_lock_commands(self.serial(), commands)
+                    .await;
+                self.settings.save().await;
+            }
+            GoXLRCommand::SetUnlockCommands(commands) => {
+                self.settings
+                    .set_device_unlock_commands(self.serial(), commands)
+                    .await;
+                self.settings.save().await;
+            }
+            GoXLRCommand::SetButtonHoldCommands(button, commands) => {
+                self.button_bindings.entry(button).or_default().hold = commands;
+                self.save_button_bindings().await;
+            }
+            GoXLRCommand::TriggerButton(button, action) => {
+                if self.is_device_mini()
+                    && !matches!(
+                        button,
+                        Button::Fader1Mute
+                            | Button::Fader2Mute
+                            | Button::Fader3Mute
+                            | Button::Fader4Mute
+                            | Button::Bleep
+                            | Button::Cough
+                    )
+                {
+                    bail!("{} is not available on the GoXLR Mini", button);
+                }
+
+                let button = standard_to_usb_button(button);
+                match action {
+                    ButtonAction::Press => {
+                        self.virtual_buttons.insert(button);
+                    }
+                    ButtonAction::Release => {
+                        self.virtual_buttons.remove(button);
+                        self.virtual_holds.remove(button);
+                    }
+                    ButtonAction::Hold => {
+                        self.virtual_buttons.insert(button);
+                        self.virtual_holds.insert(button);
+                    }
+                }
+
+                // Boxed, as button bindings can run commands which end up back here..
+                Box::pin(self.update_buttons()).await;
+            }
+            GoXLRCommand::SetButtonDoublePressCommands(button, commands) => {
+                if !commands.is_empty() && Self::acts_on_press(button) {
+                    bail!("{} acts when pressed, so can't have a double press", button);
+                }
+                self.button_bindings.entry(button).or_default().double_press = commands;
+                self.save_button_bindings().await;
+            }
+            GoXLRCommand::SetButtonReleaseCommands(button, commands) => {
+                self.button_bindings.entry(button).or_default().release = commands;
+                self.save_button_bindings().await;
+            }
             GoXLRCommand::SetSamplerPreBufferDuration(duration) => {
                 if duration > 30000 {
                     bail!("Buffer must be below 30seconds");
@@ -1885,7 +3457,9 @@ impl<'a> Device<'a> {
                 self.set_fader(fader, channel).await?;
             }
             GoXLRCommand::SetFaderMuteFunction(fader, behaviour) => {
-                if self.profile.get_mute_button_behaviour(fader) == behaviour {
+                if self.profile.get_mute_button_behaviour(fader) == behaviour
+                    && self.profile.get_mute_button_targets(fader).is_none()
+                {
                     // Settings are the same..
                     return Ok(());
                 }
@@ -1908,23 +3482,45 @@ impl<'a> Device<'a> {
                     }
                 }
             }
+            GoXLRCommand::SetFaderMuteTargets(fader, targets) => {
+                self.profile.set_mute_button_targets(fader, targets);
+                self.apply_mute_from_profile(fader, None)?;
+
+                let channel = self.profile.get_fader_assignment(fader);
+                if let Some(input) = self.get_basic_input_from_channel(channel) {
+                    self.apply_routing(input).await?;
+                }
+            }
 
             GoXLRCommand::SetVolume(channel, volume) => {
                 debug!("Setting Mix volume for {} to {}", channel, volume);
-                self.goxlr.set_volume(channel, volume)?;
-                self.profile.set_channel_volume(channel, volume)?;
-
-                // Update the Submix when volume changes via IPC
-                self.update_submix_for(channel, volume)?;
+                self.set_channel_volume(channel, volume)?;
 
-                if let Some(fader) = self.profile.get_fader_from_channel(channel) {
-                    self.fader_pause_until[fader].paused = true;
-                    self.fader_pause_until[fader].until = volume;
+                // Don't let a ramp or scene fade drag this channel back..
+                self.cancel_volume_changes(channel);
+            }
+            GoXLRCommand::AdjustVolume(channel, percent) => {
+                if !(-100..=100).contains(&percent) {
+                    bail!("Volume Adjustment must be a percentage between -100 and 100");
                 }
+                let current = i32::from(self.profile.get_channel_volume(channel));
+                let volume = (current + i32::from(percent) * 255 / 100).clamp(0, 255) as u8;
+                self.set_channel_volume(channel, volume)?;
+                self.cancel_volume_changes(channel);
+            }
+            GoXLRCommand::RampVolume(channel, volume, duration, curve) => {
+                self.cancel_volume_changes(channel);
+
+                let current = self.profile.get_channel_volume(channel);
+                let duration = Duration::from_millis(duration.into());
+                let ramp = VolumeRamp::new(current, volume, duration, curve);
+                self.volume_ramps[channel] = Some(ramp);
             }
 
             GoXLRCommand::SetCoughMuteFunction(mute_function) => {
-                if self.profile.get_chat_mute_button_behaviour() == mute_function {
+                if self.profile.get_chat_mute_button_behaviour() == mute_function
+                    && self.profile.get_chat_mute_button_targets().is_none()
+                {
                     // Settings are the same..
                     return Ok(());
                 }
@@ -1936,10 +3532,21 @@ impl<'a> Device<'a> {
                 // Reapply the Cough settings from the profile
                 self.apply_cough_from_profile()?;
                 self.apply_routing(BasicInputDevice::Microphone).await?;
+                self.apply_push_to_talk().await?;
+            }
+            GoXLRCommand::SetCoughMuteTargets(targets) => {
+                // As with the function, unmute before changing where we're muted to
+                self.unmute_chat_if_muted().await?;
+                self.profile.set_chat_mute_button_targets(targets);
+
+                self.apply_cough_from_profile()?;
+                self.apply_routing(BasicInputDevice::Microphone).await?;
+                self.apply_push_to_talk().await?;
             }
             GoXLRCommand::SetCoughIsHold(is_hold) => {
                 self.unmute_chat_if_muted().await?;
                 self.profile.set_chat_mute_button_is_held(is_hold);
+                self.apply_push_to_talk().await?;
             }
             GoXLRCommand::SetSwearButtonVolume(volume) => {
                 self.mic_profile.set_bleep_level(volume)?;
@@ -2650,6 +4257,7 @@ impl<'a> Device<'a> {
 
                 // Force load the default embedded profile..
                 self.profile = ProfileAdapter::default();
+                self.clear_mute_timers();
                 self.apply_profile(Some(volumes)).await?;
 
                 // Save the profile under a new name (although, don't overwrite if exists!)
@@ -2707,6 +4315,7 @@ impl<'a> Device<'a> {
                     }
                 };
 
+                self.clear_mute_timers();
                 self.apply_profile(Some(volumes)).await?;
                 if save_change {
                     self.settings
@@ -2870,6 +4479,20 @@ impl<'a> Device<'a> {
                 self.settings.save().await;
             }
 
+            GoXLRCommand::SetPanicMutesChat(value) => {
+                self.panic_mutes_chat = value;
+                self.settings
+                    .set_device_panic_mutes_chat(self.serial(), value)
+                    .await;
+                self.settings.save().await;
+
+                // If we're currently panicking, bring the Chat Mic in line
+                if self.panic_active {
+                    for input in BasicInputDevice::iter() {
+                        self.apply_routing(input).await?;
+                    }
+                }
+            }
             GoXLRCommand::SetVCMuteAlsoMuteCM(value) => {
                 self.vc_mute_also_mute_cm = value;
                 self.settings
@@ -2950,6 +4573,169 @@ impl<'a> Device<'a> {
                 }
             }
 
+            GoXLRCommand::SetVoiceActivityEnabled(enabled) => {
+                let mut config = self.voice_activity.config();
+                config.enabled = enabled;
+                self.set_voice_activity(config).await;
+            }
+            GoXLRCommand::SetVoiceActivityThreshold(threshold) => {
+                if !(-72..=0).contains(&threshold) {
+                    bail!("Voice Activity Threshold must be between -72 and 0");
+                }
+                let mut config = self.voice_activity.config();
+                config.threshold = threshold;
+                self.set_voice_activity(config).await;
+            }
+            GoXLRCommand::SetVoiceActivityAttack(attack) => {
+                if attack > 2000 {
+                    bail!("Voice Activity Attack cannot be greater than 2 seconds");
+                }
+                let mut config = self.voice_activity.config();
+                config.attack = attack;
+                self.set_voice_activity(config).await;
+            }
+            GoXLRCommand::SetVoiceActivityHold(hold) => {
+                if hold > 10000 {
+                    bail!("Voice Activity Hold cannot be greater than 10 seconds");
+                }
+                let mut config = self.voice_activity.config();
+                config.hold = hold;
+                self.set_voice_activity(config).await;
+            }
+
+            GoXLRCommand::SetDuckingEnabled(enabled) => {
+                let mut config = self.ducking.config();
+                config.enabled = enabled;
+                self.set_ducking(config).await;
+            }
+            GoXLRCommand::SetDuckingSource(source) => {
+                let mut config = self.ducking.config();
+                config.source = source;
+                self.set_ducking(config).await;
+            }
+            GoXLRCommand::SetDuckingInput(channel) => {
+                if matches!(
+                    channel,
+                    ChannelName::Headphones | ChannelName::MicMonitor | ChannelName::LineOut
+                ) {
+                    bail!(
+                        "{} is an output, and can't be used as a Ducking Input",
+                        channel
+                    );
+                }
+                let mut config = self.ducking.config();
+                config.input = channel;
+                self.set_ducking(config).await;
+            }
+            GoXLRCommand::SetDuckingTarget(channel, enabled) => {
+                let mut config = self.ducking.config();
+                config.targets.retain(|target| *target != channel);
+                if enabled {
+                    config.targets.push(channel);
+                }
+                self.set_ducking(config).await;
+            }
+            GoXLRCommand::SetDuckingAmount(amount) => {
+                if amount > 100 {
+                    bail!("Ducking Amount must be a percentage between 0 and 100");
+                }
+                let mut config = self.ducking.config();
+                config.amount = amount;
+                self.set_ducking(config).await;
+            }
+            GoXLRCommand::SetDuckingAttack(attack) => {
+                if attack > 5000 {
+                    bail!("Ducking Attack cannot be greater than 5 seconds");
+                }
+                let mut config = self.ducking.config();
+                config.attack = attack;
+                self.set_ducking(config).await;
+            }
+            GoXLRCommand::SetDuckingRelease(release) => {
+                if release > 10000 {
+                    bail!("Ducking Release cannot be greater than 10 seconds");
+                }
+                let mut config = self.ducking.config();
+                config.release = release;
+                self.set_ducking(config).await;
+            }
+            GoXLRCommand::SetDuckingTriggered(triggered) => {
+                if self.ducking.source() != DuckingSource::Manual {
+                    bail!("Ducking can only be triggered when the source is Manual");
+                }
+                self.ducking.set_triggered(triggered);
+            }
+
+            GoXLRCommand::SetPushToTalkEnabled(enabled) => {
+                let mut config = self.push_to_talk.config();
+                config.enabled = enabled;
+                self.set_push_to_talk(config).await?;
+            }
+            GoXLRCommand::SetPushToTalkReleaseTail(release_tail) => {
+                if release_tail > 5000 {
+                    bail!("Push to Talk Release Tail cannot be greater than 5 seconds");
+                }
+                let mut config = self.push_to_talk.config();
+                config.release_tail = release_tail;
+                self.set_push_to_talk(config).await?;
+            }
+            GoXLRCommand::SetPushToTalkLatch(latch) => {
+                let mut config = self.push_to_talk.config();
+                config.latch = latch;
+                self.set_push_to_talk(config).await?;
+            }
+
+            GoXLRCommand::SaveScene(name) => {
+                let mut scenes = self.settings.get_device_scenes(self.serial()).await;
+                scenes.insert(name, self.capture_scene());
+                self.settings.set_device_scenes(self.serial(), scenes).await;
+                self.settings.save().await;
+            }
+            GoXLRCommand::RecallScene(name, duration) => {
+                let scenes = self.settings.get_device_scenes(self.serial()).await;
+                let Some(scene) = scenes.get(&name) else {
+                    bail!("Scene {} does not exist", name);
+                };
+                let duration = Duration::from_millis(duration.into());
+                self.recall_scene(scene.clone(), duration).await?;
+            }
+            GoXLRCommand::DeleteScene(name) => {
+                let mut scenes = self.settings.get_device_scenes(self.serial()).await;
+                if scenes.remove(&name).is_none() {
+                    bail!("Scene {} does not exist", name);
+                }
+                self.settings.set_device_scenes(self.serial(), scenes).await;
+                self.settings.save().await;
+            }
+
+            GoXLRCommand::SetRule(name, rule) => {
+                let mut rules = self.settings.get_device_rules(self.serial()).await;
+                rules.insert(name, rule);
+                self.settings.set_device_rules(self.serial(), rules).await;
+                self.settings.save().await;
+            }
+            GoXLRCommand::SetRuleEnabled(name, enabled) => {
+                let mut rules = self.settings.get_device_rules(self.serial()).await;
+                let Some(rule) = rules.get_mut(&name) else {
+                    bail!("Rule {} does not exist", name);
+                };
+                rule.enabled = enabled;
+                self.settings.set_device_rules(self.serial(), rules).await;
+                self.settings.save().await;
+            }
+            GoXLRCommand::DeleteRule(name) => {
+                let mut rules = self.settings.get_device_rules(self.serial()).await;
+                if rules.remove(&name).is_none() {
+                    bail!("Rule {} does not exist", name);
+                }
+                self.settings.set_device_rules(self.serial(), rules).await;
+                self.settings.save().await;
+            }
+
+            GoXLRCommand::Undo() => self.undo().await?,
+            GoXLRCommand::Redo() => self.redo().await?,
+            GoXLRCommand::ClearHistory() => self.history.clear(),
+
             GoXLRCommand::SetActiveEffectPreset(preset) => {
                 self.load_effect_bank(preset).await?;
                 self.update_button_states()?;
@@ -2979,30 +4765,45 @@ impl<'a> Device<'a> {
                 MuteState::MutedToX => self.mute_fader_to_x(fader).await?,
                 MuteState::MutedToAll => self.mute_fader_to_all(fader, true).await?,
             },
+            GoXLRCommand::SetChannelMute(channel, function) => {
+                self.set_channel_mute(channel, function).await?;
+            }
             GoXLRCommand::SetCoughMuteState(state) => {
-                // This is more complicated because the 'state' of the mute can come from
-                // various different locations, so what we're going to do is simply update
-                // the profile, and re-apply the Mute settings from there.
-                if !self.profile.is_mute_chat_button_toggle() {
-                    bail!("Cannot Set state when Mute button is in 'Hold' Mode");
-                }
-                match state {
-                    MuteState::Unmuted => {
-                        self.profile.set_mute_chat_button_on(false);
-                        self.profile.set_mute_chat_button_blink(false);
-                    }
-                    MuteState::MutedToX => {
-                        self.profile.set_mute_chat_button_on(true);
-                        self.profile.set_mute_chat_button_blink(false);
+                self.set_cough_mute_state(state).await?;
+            }
+            GoXLRCommand::SetPanicActive(active) => {
+                self.set_panic(active).await?;
+            }
+            GoXLRCommand::SetFaderMuteTimer(fader, seconds, announce) => {
+                if seconds == 0 {
+                    self.fader_mute_timers[fader] = None;
+                } else {
+                    // Mute the same way a button press would, if we're not already muted
+                    let (muted_to_x, muted_to_all, _) = self.profile.get_mute_button_state(fader);
+                    if !muted_to_x && !muted_to_all {
+                        self.mute_fader_to_x(fader).await?;
                     }
-                    MuteState::MutedToAll => {
-                        self.profile.set_mute_chat_button_on(true);
-                        self.profile.set_mute_chat_button_blink(true);
+                    let duration = Duration::from_secs(seconds.into());
+                    self.fader_mute_timers[fader] = Some(MuteTimer::new(duration, announce));
+                }
+                self.update_button_states()?;
+            }
+            GoXLRCommand::SetCoughMuteTimer(seconds, announce) => {
+                if seconds == 0 {
+                    self.cough_mute_timer = None;
+                } else {
+                    let (_, muted_to_x, muted_to_all, _) =
+                        self.profile.get_mute_chat_button_state();
+                    if !muted_to_x && !muted_to_all {
+                        self.set_cough_mute_state(MuteState::MutedToX).await?;
+                    } else if !self.profile.is_mute_chat_button_toggle()
+                        || self.push_to_talk.is_enabled()
+                    {
+                        bail!("Timed Mutes require the Cough button to be in 'Toggle' Mode");
                     }
+                    let duration = Duration::from_secs(seconds.into());
+                    self.cough_mute_timer = Some(MuteTimer::new(duration, announce));
                 }
-                self.apply_cough_from_profile()?;
-                self.apply_effects(LinkedHashSet::from_iter([EffectKey::MicInputMute]))?;
-                self.apply_routing(BasicInputDevice::Microphone).await?;
                 self.update_button_states()?;
             }
             GoXLRCommand::SetSubMixEnabled(enabled) => {
@@ -3021,8 +4822,22 @@ impl<'a> Device<'a> {
                 }
             }
             GoXLRCommand::SetSubMixVolume(channel, volume) => {
+                self.submix_ramps[channel] = None;
                 self.apply_submix_volume(channel, volume)?;
             }
+            GoXLRCommand::RampSubMixVolume(channel, volume, duration, curve) => {
+                if !self.submix_active() {
+                    bail!("Sub Mixes are not enabled");
+                }
+                let Some(mix) = self.profile.get_submix_from_channel(channel) else {
+                    bail!("Channel {} does not have a Sub Mix", channel);
+                };
+
+                let current = self.profile.get_submix_volume(mix);
+                let duration = Duration::from_millis(duration.into());
+                let ramp = VolumeRamp::new(current, volume, duration, curve);
+                self.submix_ramps[channel] = Some(ramp);
+            }
             GoXLRCommand::SetSubMixLinked(channel, linked) => {
                 self.link_submix_channel(channel, linked)?;
             }
@@ -3153,6 +4968,29 @@ impl<'a> Device<'a> {
 
         // Replace the Cough Button button data with correct data.
         result[Buttons::MicrophoneMute as usize] = self.profile.get_mute_chat_button_colour_state();
+
+        // While panicking, flash every mute button so it's obvious nothing's going out
+        if self.panic_active {
+            for fader in FaderName::iter() {
+                result[fader_mute_button(fader) as usize] = ButtonStates::Flashing;
+            }
+            result[Buttons::MicrophoneMute as usize] = ButtonStates::Flashing;
+        }
+
+        // Flash the buttons of timed mutes which are about to run out. This is done here rather
+        // than through the profile's blink state, as that's used to track 'Muted to All'.
+        for fader in FaderName::iter() {
+            if let Some(timer) = &self.fader_mute_timers[fader]
+                && timer.is_warning()
+            {
+                result[fader_mute_button(fader) as usize] = ButtonStates::Flashing;
+            }
+        }
+        if let Some(timer) = &self.cough_mute_timer
+            && timer.is_warning()
+        {
+            result[Buttons::MicrophoneMute as usize] = ButtonStates::Flashing;
+        }
         result
     }
 
@@ -3244,6 +5082,12 @@ impl<'a> Device<'a> {
             }
         }
 
+        // Channels can also be muted without being on a fader
+        if let Some(mute_function) = self.profile.get_channel_mute(channel_name) {
+            self.apply_transient_channel_routing(channel_name, true, false, mute_function, router)
+                .await?;
+        }
+
         // Chat Mic has a Transient routing option related to the Voice Chat channel, we need
         // to ensure that if we're handling the mic, we handle it here.
         if channel_name == ChannelName::Mic {
@@ -3261,6 +5105,13 @@ impl<'a> Device<'a> {
         router: &mut EnumMap<BasicOutputDevice, bool>,
     ) -> Result<()> {
         let (muted_to_x, muted_to_all, mute_function) = self.profile.get_mute_button_state(fader);
+        if muted_to_x
+            && !muted_to_all
+            && let Some(targets) = self.profile.get_mute_button_targets(fader)
+        {
+            return self.apply_transient_target_routing(&targets, router).await;
+        }
+
         self.apply_transient_channel_routing(
             channel_name,
             muted_to_x,
@@ -3279,6 +5130,13 @@ impl<'a> Device<'a> {
         let (_mute_toggle, muted_to_x, muted_to_all, mute_function) =
             self.profile.get_mute_chat_button_state();
 
+        if muted_to_x
+            && !muted_to_all
+            && let Some(targets) = self.profile.get_chat_mute_button_targets()
+        {
+            return self.apply_transient_target_routing(&targets, router).await;
+        }
+
         self.apply_transient_channel_routing(
             ChannelName::Mic,
             muted_to_x,
@@ -3289,6 +5147,27 @@ impl<'a> Device<'a> {
         .await
     }
 
+    async fn apply_transient_target_routing(
+        &self,
+        targets: &[BasicOutputDevice],
+        router: &mut EnumMap<BasicOutputDevice, bool>,
+    ) -> Result<()> {
+        for target in targets {
+            router[*target] = false;
+        }
+
+        // As with Mute to Stream, the VOD channel follows the Stream Mix in 'Stream No Music'
+        if targets.contains(&BasicOutputDevice::BroadcastMix) && self.is_stream_no_music().await {
+            let channel = if self.device_supports_mix2() {
+                BasicOutputDevice::StreamMix2
+            } else {
+                BasicOutputDevice::Sampler
+            };
+            router[channel] = false;
+        }
+        Ok(())
+    }
+
     fn apply_transient_chat_mic_mute(
         &self,
         router: &mut EnumMap<BasicOutputDevice, bool>,
@@ -3308,6 +5187,10 @@ impl<'a> Device<'a> {
             }
         }
 
+        if self.vc_mute_also_mute_cm && self.channel_muted_to_all(ChannelName::Chat) {
+            router[BasicOutputDevice::ChatMic] = false;
+        }
+
         Ok(())
     }
 
@@ -3382,6 +5265,15 @@ impl<'a> Device<'a> {
             }
         }
 
+        // Panic clears the stream before the VOD sync below, so the VOD mix follows along
+        if self.panic_active {
+            router[BasicOutputDevice::BroadcastMix] = false;
+            router[BasicOutputDevice::StreamMix2] = false;
+            if self.panic_mutes_chat {
+                router[BasicOutputDevice::ChatMic] = false;
+            }
+        }
+
         if self.is_stream_no_music().await {
             // Ok, so we need to sync the Mix channel to the Sample (VOD) Channel, unless Music
             let channel = if self.device_supports_mix2() {
@@ -3445,13 +5337,13 @@ impl<'a> Device<'a> {
         if let Some(current) = current {
             if current != Unmuted {
                 debug!("Channel {} set to Unmuted (change from previous)", channel);
-                self.goxlr.set_channel_state(channel, Unmuted)?;
+                self.set_channel_unmuted(channel)?;
             } else {
                 debug!("Channel {} already Unmuted, doing nothing.", fader);
             }
         } else {
             debug!("Channel {} set to Unmuted (no previous)", channel);
-            self.goxlr.set_channel_state(channel, Unmuted)?;
+            self.set_channel_unmuted(channel)?;
         }
 
         Ok(())
@@ -3484,7 +5376,7 @@ impl<'a> Device<'a> {
             self.goxlr.set_channel_state(ChannelName::Mic, Muted)?;
         } else {
             debug!("Setting Mic to Unmuted");
-            self.goxlr.set_channel_state(ChannelName::Mic, Unmuted)?;
+            self.set_channel_unmuted(ChannelName::Mic)?;
         }
         Ok(())
     }
@@ -3596,10 +5488,14 @@ impl<'a> Device<'a> {
         FaderStatus {
             channel: self.profile().get_fader_assignment(fader),
             mute_type: self.profile().get_mute_button_behaviour(fader),
+            mute_targets: self.profile.get_mute_button_targets(fader),
             scribble: self
                 .profile()
                 .get_scribble_ipc(fader, self.is_device_mini()),
             mute_state: self.profile.get_ipc_mute_state(fader),
+            mute_timer: self.fader_mute_timers[fader]
+                .as_ref()
+                .map(MuteTimer::remaining_secs),
         }
     }
 
@@ -3676,6 +5572,11 @@ impl<'a> Device<'a> {
     }
 
     async fn apply_profile(&mut self, current: Option<CurrentState>) -> Result<()> {
+        // Any running fades or ramps were heading for the old profile's volumes..
+        self.scene_fade = None;
+        self.volume_ramps = EnumMap::default();
+        self.submix_ramps = EnumMap::default();
+
         // Set volumes first, applying mute may modify stuff..
         debug!("Applying Profile..");
 
@@ -3714,14 +5615,22 @@ impl<'a> Device<'a> {
             } else if let Some(current) = &current {
                 if current.mute_state[channel] != Unmuted {
                     debug!("Channel {} not on Fader, but muted. Unmuting..", channel);
-                    self.goxlr.set_channel_state(channel, Unmuted)?;
+                    self.set_channel_unmuted(channel)?;
                 }
             } else {
                 debug!("Unknown Channel state for {}, Unmuting.", channel);
-                self.goxlr.set_channel_state(channel, Unmuted)?;
+                self.set_channel_unmuted(channel)?;
+            }
+
+            if self.channel_muted_to_all(channel) {
+                debug!("Channel {} is Muted to All by the Profile", channel);
+                self.goxlr.set_channel_state(channel, Muted)?;
             }
         }
 
+        // Push to Talk keeps the mic closed regardless of what the profile says
+        self.apply_push_to_talk().await?;
+
         debug!("Setting Channel Volumes..");
         let volumes = if let Some(current) = &current {
             self.get_load_volume_order(Some(current.volumes))
@@ -3736,6 +5645,10 @@ impl<'a> Device<'a> {
             self.goxlr.set_volume(channel, channel_volume)?;
         }
 
+        // The volumes above are unducked, so if we're currently ducking put it back.
+        self.ducked_volumes = EnumMap::default();
+        self.apply_ducking()?;
+
         debug!("Applying Submixing Settings..");
         self.load_submix_settings(true).await?;
 
@@ -4211,6 +6124,15 @@ fn tts_bool_to_state(bool: bool) -> String {
     }
 }
 
+fn fader_mute_button(fader: FaderName) -> Buttons {
+    match fader {
+        FaderName::A => Buttons::Fader1Mute,
+        FaderName::B => Buttons::Fader2Mute,
+        FaderName::C => Buttons::Fader3Mute,
+        FaderName::D => Buttons::Fader4Mute,
+    }
+}
+
 fn tts_target(target: MuteFunction) -> String {
     match target {
         MuteFunction::All => "".to_string(),
@@ -0,0 +1,109 @@
+use goxlr_ipc::Ducking;
+use goxlr_types::{ChannelName, DuckingSource};
+use std::time::Instant;
+
+/*
+   Ducking lowers a set of target channels while a source is active. This only tracks how far
+   'in' the duck currently is (0.0 being not ducked at all, 1.0 being fully ducked), ramping
+   towards the target over the attack and release times. The actual volumes are derived from
+   the profile volumes whenever they're needed, so the profile itself is never modified.
+*/
+pub struct DuckingEngine {
+    config: Ducking,
+    triggered: bool,
+    level: f64,
+    last_update: Option<Instant>,
+}
+
+impl DuckingEngine {
+    pub fn new(config: Ducking) -> Self {
+        Self {
+            config,
+            triggered: false,
+            level: 0.0,
+            last_update: None,
+        }
+    }
+
+    pub fn config(&self) -> Ducking {
+        self.config.clone()
+    }
+
+    pub fn set_config(&mut self, config: Ducking) {
+        self.config = config;
+    }
+
+    pub fn source(&self) -> DuckingSource {
+        self.config.source
+    }
+
+    pub fn input(&self) -> ChannelName {
+        self.config.input
+    }
+
+    /// Returns true if the Mic level needs to be sampled to drive the duck
+    pub fn needs_mic_level(&self) -> bool {
+        self.config.enabled && self.config.source == DuckingSource::Microphone
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        self.triggered
+    }
+
+    pub fn set_triggered(&mut self, triggered: bool) {
+        self.triggered = triggered;
+    }
+
+    pub fn is_ducked(&self) -> bool {
+        self.level > 0.0
+    }
+
+    /// Moves the duck towards its target based on the source state, returns true if it moved
+    pub fn update(&mut self, active: bool) -> bool {
+        let now = Instant::now();
+        let elapsed = self
+            .last_update
+            .map(|last| now.duration_since(last))
+            .unwrap_or_default();
+        self.last_update = Some(now);
+
+        let target = if self.config.enabled && active {
+            1.0
+        } else {
+            0.0
+        };
+
+        if self.level == target {
+            return false;
+        }
+
+        let ramp = if target > self.level {
+            self.config.attack
+        } else {
+            self.config.release
+        };
+
+        // A ramp of 0 should jump straight to the target..
+        let step = match ramp {
+            0 => 1.0,
+            ramp => elapsed.as_secs_f64() * 1000.0 / f64::from(ramp),
+        };
+
+        self.level = if target > self.level {
+            (self.level + step).min(target)
+        } else {
+            (self.level - step).max(target)
+        };
+        true
+    }
+
+    /// Returns the volume a channel should be at given its profile volume
+    pub fn volume_for(&self, channel: ChannelName, volume: u8) -> u8 {
+        if self.level == 0.0 || !self.config.targets.contains(&channel) {
+            return volume;
+        }
+
+        let reduction = f64::from(self.config.amount.min(100)) / 100.0 * self.level;
+        (f64::from(volume) * (1.0 - reduction)).round() as u8
+    }
+}
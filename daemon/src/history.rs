@@ -0,0 +1,123 @@
+use goxlr_ipc::{GoXLRCommand, History as HistoryStatus};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::mem::{Discriminant, discriminant};
+use std::time::{Duration, Instant};
+
+// How many changes can be undone, each entry holds a full copy of both profiles.
+const HISTORY_LIMIT: usize = 50;
+
+// Repeats of the same command inside this window (for example, dragging an EQ slider) are
+// folded into a single entry, so undo goes back to where the drag started.
+const MERGE_WINDOW: Duration = Duration::from_secs(1);
+
+// The command, along with every argument other than the value being set.
+type ChangeTarget = (Discriminant<GoXLRCommand>, Option<Value>);
+
+// An in-memory copy of the profile and mic profile, taken before a change is made.
+#[derive(Clone)]
+pub struct ProfileSnapshot {
+    pub profile_name: String,
+    pub profile: Vec<u8>,
+    pub mic_profile_name: String,
+    pub mic_profile: Vec<u8>,
+}
+
+pub struct HistoryEntry {
+    pub description: String,
+    pub snapshot: ProfileSnapshot,
+}
+
+#[derive(Default)]
+pub struct History {
+    undo: VecDeque<HistoryEntry>,
+    redo: Vec<HistoryEntry>,
+    last_change: Option<(ChangeTarget, Instant)>,
+}
+
+impl History {
+    /// Returns true if this command carries on from the last change, and needs no new entry
+    pub fn is_continuation(&self, command: &GoXLRCommand) -> bool {
+        if self.undo.is_empty() {
+            return false;
+        }
+
+        self.last_change.as_ref().is_some_and(|(last, time)| {
+            *last == change_target(command) && time.elapsed() < MERGE_WINDOW
+        })
+    }
+
+    /// Records a change, the snapshot should be None if the command is a continuation
+    pub fn record(&mut self, command: &GoXLRCommand, snapshot: Option<ProfileSnapshot>) {
+        if let Some(snapshot) = snapshot {
+            self.push_undo(HistoryEntry {
+                description: format!("{command:?}"),
+                snapshot,
+            });
+        }
+        self.redo.clear();
+        self.last_change = Some((change_target(command), Instant::now()));
+    }
+
+    pub fn pop_undo(&mut self) -> Option<HistoryEntry> {
+        self.last_change = None;
+        self.undo.pop_back()
+    }
+
+    pub fn pop_redo(&mut self) -> Option<HistoryEntry> {
+        self.last_change = None;
+        self.redo.pop()
+    }
+
+    pub fn push_undo(&mut self, entry: HistoryEntry) {
+        if self.undo.len() == HISTORY_LIMIT {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(entry);
+    }
+
+    pub fn push_redo(&mut self, entry: HistoryEntry) {
+        self.redo.push(entry);
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.last_change = None;
+    }
+
+    pub fn status(&self) -> HistoryStatus {
+        HistoryStatus {
+            undo: self
+                .undo
+                .iter()
+                .rev()
+                .map(|e| e.description.clone())
+                .collect(),
+            redo: self
+                .redo
+                .iter()
+                .rev()
+                .map(|e| e.description.clone())
+                .collect(),
+        }
+    }
+}
+
+// The value being set is always the last argument of a command, so anything before it is what's
+// being changed. This means SetEqGain on two different frequencies aren't treated as one drag.
+fn change_target(command: &GoXLRCommand) -> ChangeTarget {
+    let arguments = match serde_json::to_value(command) {
+        Ok(Value::Object(variant)) => variant.into_iter().next().map(|(_, value)| value),
+        _ => None,
+    };
+
+    let target = match arguments {
+        Some(Value::Array(mut arguments)) => {
+            arguments.pop();
+            Some(Value::Array(arguments))
+        }
+        _ => None,
+    };
+    (discriminant(command), target)
+}
@@ -0,0 +1,334 @@
+use enum_map::EnumMap;
+use goxlr_ipc::{GoXLRCommand, LinkGroup, RuleEffect};
+use goxlr_types::{ChannelName, EffectBankPresets, FaderName, MuteFunction, MuteState};
+use strum::IntoEnumIterator;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LinkCategory {
+    Volumes,
+    Mutes,
+    Lighting,
+    Effects,
+}
+
+impl LinkCategory {
+    pub fn is_linked(&self, group: &LinkGroup) -> bool {
+        match self {
+            LinkCategory::Volumes => group.volumes,
+            LinkCategory::Mutes => group.mutes,
+            LinkCategory::Lighting => group.lighting,
+            LinkCategory::Effects => group.effects,
+        }
+    }
+}
+
+// A change to pass on to linked devices
+#[derive(Debug, Clone)]
+pub enum LinkChange {
+    Command(GoXLRCommand),
+
+    // Fader mutes are matched up by channel, as a linked device may have it on a different
+    // fader, the function is what the fader mutes to when Muted to X.
+    FaderMute(ChannelName, MuteState, MuteFunction),
+}
+
+impl LinkChange {
+    /// Returns the command to run on a linked device, given where it has each channel
+    pub fn command_for(
+        &self,
+        fader_for: impl Fn(ChannelName) -> Option<FaderName>,
+    ) -> GoXLRCommand {
+        match self {
+            LinkChange::Command(command) => command.clone(),
+            LinkChange::FaderMute(channel, state, function) => match fader_for(*channel) {
+                Some(fader) => GoXLRCommand::SetFaderMuteState(fader, *state),
+
+                // Not on a fader here, so mute the channel directly instead
+                None => {
+                    let function = match state {
+                        MuteState::Unmuted => None,
+                        MuteState::MutedToX => Some(*function),
+                        MuteState::MutedToAll => Some(MuteFunction::All),
+                    };
+                    GoXLRCommand::SetChannelMute(*channel, function)
+                }
+            },
+        }
+    }
+}
+
+/*
+   Volumes, mutes and the effect buttons can all be changed from the hardware, so rather than
+   watching for commands we keep a snapshot of them, and build the commands to send to linked
+   devices from whatever has changed since the last one.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkState {
+    pub volumes: EnumMap<ChannelName, u8>,
+
+    // The mute state of each channel currently assigned to a fader
+    pub fader_mutes: EnumMap<ChannelName, Option<(MuteState, MuteFunction)>>,
+    pub channel_mutes: EnumMap<ChannelName, Option<MuteFunction>>,
+    pub cough_mute: MuteState,
+    pub effect_preset: EffectBankPresets,
+    pub effects: EnumMap<RuleEffect, bool>,
+}
+
+impl LinkState {
+    /// Tracks a volume change which shouldn't be passed on, such as a scene fading in
+    pub fn follow_volume(&mut self, channel: ChannelName, volume: u8) {
+        self.volumes[channel] = volume;
+    }
+
+    pub fn changes(&self, current: &LinkState) -> Vec<(LinkCategory, LinkChange)> {
+        let mut changes = vec![];
+        for channel in ChannelName::iter() {
+            if self.volumes[channel] != current.volumes[channel] {
+                let command = GoXLRCommand::SetVolume(channel, current.volumes[channel]);
+                changes.push((LinkCategory::Volumes, LinkChange::Command(command)));
+            }
+        }
+
+        for channel in ChannelName::iter() {
+            if let Some((state, function)) = current.fader_mutes[channel]
+                && self.fader_mutes[channel] != Some((state, function))
+            {
+                let change = LinkChange::FaderMute(channel, state, function);
+                changes.push((LinkCategory::Mutes, change));
+            }
+            if self.channel_mutes[channel] != current.channel_mutes[channel] {
+                let command = GoXLRCommand::SetChannelMute(channel, current.channel_mutes[channel]);
+                changes.push((LinkCategory::Mutes, LinkChange::Command(command)));
+            }
+        }
+        if self.cough_mute != current.cough_mute {
+            let command = GoXLRCommand::SetCoughMuteState(current.cough_mute);
+            changes.push((LinkCategory::Mutes, LinkChange::Command(command)));
+        }
+
+        // The preset goes first, as changing it can change which effects are enabled.
+        if self.effect_preset != current.effect_preset {
+            let command = GoXLRCommand::SetActiveEffectPreset(current.effect_preset);
+            changes.push((LinkCategory::Effects, LinkChange::Command(command)));
+        }
+        for (effect, &enabled) in &current.effects {
+            if self.effects[effect] != enabled {
+                let command = match effect {
+                    RuleEffect::Fx => GoXLRCommand::SetFXEnabled(enabled),
+                    RuleEffect::Megaphone => GoXLRCommand::SetMegaphoneEnabled(enabled),
+                    RuleEffect::Robot => GoXLRCommand::SetRobotEnabled(enabled),
+                    RuleEffect::HardTune => GoXLRCommand::SetHardTuneEnabled(enabled),
+                };
+                changes.push((LinkCategory::Effects, LinkChange::Command(command)));
+            }
+        }
+        changes
+    }
+}
+
+/// Returns the category for commands which are passed to linked devices as they are, these
+/// are things which can only be changed by a command, so aren't part of the LinkState.
+pub fn forwarded_category(command: &GoXLRCommand) -> Option<LinkCategory> {
+    match command {
+        GoXLRCommand::SetAnimationMode(_)
+        | GoXLRCommand::SetAnimationMod1(_)
+        | GoXLRCommand::SetAnimationMod2(_)
+        | GoXLRCommand::SetAnimationWaterfall(_)
+        | GoXLRCommand::SetGlobalColour(_)
+        | GoXLRCommand::SetFaderDisplayStyle(..)
+        | GoXLRCommand::SetFaderColours(..)
+        | GoXLRCommand::SetAllFaderColours(..)
+        | GoXLRCommand::SetAllFaderDisplayStyle(_)
+        | GoXLRCommand::SetButtonColours(..)
+        | GoXLRCommand::SetButtonOffStyle(..)
+        | GoXLRCommand::SetButtonGroupColours(..)
+        | GoXLRCommand::SetButtonGroupOffStyle(..)
+        | GoXLRCommand::SetSimpleColour(..)
+        | GoXLRCommand::SetEncoderColour(..)
+        | GoXLRCommand::SetSampleColour(..)
+        | GoXLRCommand::SetSampleOffStyle(..) => Some(LinkCategory::Lighting),
+
+        GoXLRCommand::SetReverbStyle(_)
+        | GoXLRCommand::SetReverbAmount(_)
+        | GoXLRCommand::SetReverbDecay(_)
+        | GoXLRCommand::SetReverbEarlyLevel(_)
+        | GoXLRCommand::SetReverbTailLevel(_)
+        | GoXLRCommand::SetReverbPreDelay(_)
+        | GoXLRCommand::SetReverbLowColour(_)
+        | GoXLRCommand::SetReverbHighColour(_)
+        | GoXLRCommand::SetReverbHighFactor(_)
+        | GoXLRCommand::SetReverbDiffuse(_)
+        | GoXLRCommand::SetReverbModSpeed(_)
+        | GoXLRCommand::SetReverbModDepth(_)
+        | GoXLRCommand::SetEchoStyle(_)
+        | GoXLRCommand::SetEchoAmount(_)
+        | GoXLRCommand::SetEchoFeedback(_)
+        | GoXLRCommand::SetEchoTempo(_)
+        | GoXLRCommand::SetEchoDelayLeft(_)
+        | GoXLRCommand::SetEchoDelayRight(_)
+        | GoXLRCommand::SetEchoFeedbackLeft(_)
+        | GoXLRCommand::SetEchoFeedbackRight(_)
+        | GoXLRCommand::SetEchoFeedbackXFBLtoR(_)
+        | GoXLRCommand::SetEchoFeedbackXFBRtoL(_)
+        | GoXLRCommand::SetPitchStyle(_)
+        | GoXLRCommand::SetPitchAmount(_)
+        | GoXLRCommand::SetPitchCharacter(_)
+        | GoXLRCommand::SetGenderStyle(_)
+        | GoXLRCommand::SetGenderAmount(_)
+        | GoXLRCommand::SetMegaphoneStyle(_)
+        | GoXLRCommand::SetMegaphoneAmount(_)
+        | GoXLRCommand::SetMegaphonePostGain(_)
+        | GoXLRCommand::SetRobotStyle(_)
+        | GoXLRCommand::SetRobotGain(..)
+        | GoXLRCommand::SetRobotFreq(..)
+        | GoXLRCommand::SetRobotWidth(..)
+        | GoXLRCommand::SetRobotWaveform(_)
+        | GoXLRCommand::SetRobotPulseWidth(_)
+        | GoXLRCommand::SetRobotThreshold(_)
+        | GoXLRCommand::SetRobotDryMix(_)
+        | GoXLRCommand::SetHardTuneStyle(_)
+        | GoXLRCommand::SetHardTuneAmount(_)
+        | GoXLRCommand::SetHardTuneRate(_)
+        | GoXLRCommand::SetHardTuneWindow(_)
+        | GoXLRCommand::SetHardTuneSource(_) => Some(LinkCategory::Effects),
+
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> LinkState {
+        LinkState {
+            volumes: EnumMap::default(),
+            fader_mutes: EnumMap::default(),
+            channel_mutes: EnumMap::default(),
+            cough_mute: MuteState::Unmuted,
+            effect_preset: EffectBankPresets::Preset1,
+            effects: EnumMap::default(),
+        }
+    }
+
+    fn commands(changes: &[(LinkCategory, LinkChange)]) -> Vec<GoXLRCommand> {
+        changes
+            .iter()
+            .map(|(_, change)| change.command_for(|_| Some(FaderName::A)))
+            .collect()
+    }
+
+    #[test]
+    fn unchanged_state_sends_nothing() {
+        assert!(state().changes(&state()).is_empty());
+    }
+
+    #[test]
+    fn volume_changes_are_sent() {
+        let mut current = state();
+        current.volumes[ChannelName::Music] = 120;
+
+        let changes = state().changes(&current);
+        assert!(matches!(changes[..], [(LinkCategory::Volumes, _)]));
+        assert!(matches!(
+            commands(&changes)[..],
+            [GoXLRCommand::SetVolume(ChannelName::Music, 120)]
+        ));
+    }
+
+    #[test]
+    fn followed_volumes_are_not_sent() {
+        let mut previous = state();
+        let mut current = state();
+
+        // A scene fading the music in, while the mic is muted by hand
+        current.volumes[ChannelName::Music] = 120;
+        current.fader_mutes[ChannelName::Mic] = Some((MuteState::MutedToAll, MuteFunction::All));
+        previous.follow_volume(ChannelName::Music, 120);
+
+        let changes = previous.changes(&current);
+        assert!(matches!(changes[..], [(LinkCategory::Mutes, _)]));
+    }
+
+    #[test]
+    fn fader_mutes_are_sent_by_channel() {
+        let mut previous = state();
+        previous.fader_mutes[ChannelName::Game] = Some((MuteState::Unmuted, MuteFunction::All));
+
+        let mut current = previous.clone();
+        current.fader_mutes[ChannelName::Game] =
+            Some((MuteState::MutedToX, MuteFunction::ToStream));
+
+        // Moving a channel off the faders isn't a mute, so shouldn't be sent
+        current.fader_mutes[ChannelName::Music] = None;
+
+        let changes = previous.changes(&current);
+        assert!(matches!(
+            changes[..],
+            [(
+                LinkCategory::Mutes,
+                LinkChange::FaderMute(
+                    ChannelName::Game,
+                    MuteState::MutedToX,
+                    MuteFunction::ToStream
+                )
+            )]
+        ));
+    }
+
+    #[test]
+    fn channel_mutes_are_sent() {
+        let mut current = state();
+        current.channel_mutes[ChannelName::System] = Some(MuteFunction::All);
+
+        let changes = state().changes(&current);
+        assert!(matches!(
+            commands(&changes)[..],
+            [GoXLRCommand::SetChannelMute(
+                ChannelName::System,
+                Some(MuteFunction::All)
+            )]
+        ));
+
+        let changes = current.changes(&state());
+        assert!(matches!(
+            commands(&changes)[..],
+            [GoXLRCommand::SetChannelMute(ChannelName::System, None)]
+        ));
+    }
+
+    #[test]
+    fn fader_mutes_use_the_linked_devices_fader() {
+        let change =
+            LinkChange::FaderMute(ChannelName::Game, MuteState::MutedToX, MuteFunction::All);
+        let command = change.command_for(|channel| match channel {
+            ChannelName::Game => Some(FaderName::C),
+            _ => None,
+        });
+        assert!(matches!(
+            command,
+            GoXLRCommand::SetFaderMuteState(FaderName::C, MuteState::MutedToX)
+        ));
+    }
+
+    #[test]
+    fn fader_mutes_fall_back_to_channel_mutes() {
+        let to_channel = |state| {
+            let change = LinkChange::FaderMute(ChannelName::Game, state, MuteFunction::ToStream);
+            change.command_for(|_| None)
+        };
+
+        assert!(matches!(
+            to_channel(MuteState::Unmuted),
+            GoXLRCommand::SetChannelMute(ChannelName::Game, None)
+        ));
+        assert!(matches!(
+            to_channel(MuteState::MutedToX),
+            GoXLRCommand::SetChannelMute(ChannelName::Game, Some(MuteFunction::ToStream))
+        ));
+        assert!(matches!(
+            to_channel(MuteState::MutedToAll),
+            GoXLRCommand::SetChannelMute(ChannelName::Game, Some(MuteFunction::All))
+        ));
+    }
+}
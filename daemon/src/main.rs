@@ -26,8 +26,10 @@ use tokio::join;
 use tokio::sync::{broadcast, mpsc, oneshot};
 
 use goxlr_ipc::{FirmwareSource, HttpSettings, LogLevel};
+use goxlr_types::DeviceType;
+use goxlr_usb::device::emulated::Emulation;
 
-use crate::cli::{Cli, LevelFilter};
+use crate::cli::{Cli, EmulatedDevice, LevelFilter};
 use crate::events::{DaemonState, EventTriggers, spawn_event_handler};
 use crate::files::{FileManager, spawn_file_notification_service};
 use crate::platform::perform_preflight;
@@ -39,21 +41,32 @@ use crate::settings::SettingsHandle;
 use crate::shutdown::Shutdown;
 use crate::tts::spawn_tts_service;
 
+mod app_rules;
 mod audio;
 mod cli;
 mod device;
+mod ducking;
 mod events;
 mod files;
 mod firmware;
+mod history;
+mod links;
 mod mic_profile;
+mod mute_timer;
 mod platform;
 mod primary_worker;
 mod profile;
+mod push_to_talk;
+mod ramps;
+mod rules;
+mod scenes;
+mod scheduler;
 mod servers;
 mod settings;
 mod shutdown;
 mod tray;
 mod tts;
+mod voice_activity;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -248,6 +261,25 @@ async fn run_utility() -> Result<()> {
         OVERRIDE_SAMPLER_OUTPUT.lock().unwrap().replace(device);
     }
 
+    let mut emulated_devices: Vec<Emulation> = args
+        .emulate_device
+        .iter()
+        .map(|device| match device {
+            EmulatedDevice::Full => Emulation::Device(DeviceType::Full),
+            EmulatedDevice::Mini => Emulation::Device(DeviceType::Mini),
+        })
+        .collect();
+
+    for path in args.replay_capture {
+        emulated_devices.push(Emulation::Replay(path));
+    }
+
+    if let Some(path) = &args.capture_usb
+        && let Err(e) = create_dir_all(path)
+    {
+        bail!("Unable to create USB capture directory: {}", e);
+    }
+
     info!("Starting GoXLR Daemon v{}", VERSION);
     info!("System Locale: {}", *SYSTEM_LOCALE);
 
@@ -325,6 +357,7 @@ async fn run_utility() -> Result<()> {
     // Start the USB Device Handler
     let usb_handle = tokio::spawn(spawn_usb_handler(
         usb_rx,
+        usb_tx.clone(),
         file_rx,
         device_state_rx,
         broadcast_tx.clone(),
@@ -334,6 +367,8 @@ async fn run_utility() -> Result<()> {
         settings.clone(),
         http_settings.clone(),
         file_manager,
+        emulated_devices,
+        args.capture_usb,
     ));
 
     // Wait until the handler is setup
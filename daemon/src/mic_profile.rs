@@ -7,8 +7,8 @@ use goxlr_ipc::{Compressor, Equaliser, EqualiserMini, NoiseGate};
 use goxlr_profile_loader::components::mute::MuteFunction;
 use goxlr_profile_loader::mic_profile::MicProfileSettings;
 use goxlr_types::{
-    CompressorAttackTime, CompressorRatio, CompressorReleaseTime, DisplayMode, EffectKey,
-    EqFrequencies, GateTimes, MicrophoneParamKey, MicrophoneType, MiniEqFrequencies,
+    ChannelName, CompressorAttackTime, CompressorRatio, CompressorReleaseTime, DisplayMode,
+    EffectKey, EqFrequencies, GateTimes, MicrophoneParamKey, MicrophoneType, MiniEqFrequencies,
 };
 use log::warn;
 use ritelinked::LinkedHashSet;
@@ -67,6 +67,17 @@ impl MicProfileAdapter {
         Ok(Self { name, profile })
     }
 
+    // An in-memory copy of the profile, so changes can be rolled back without touching the disk.
+    pub fn snapshot(&self) -> Result<Vec<u8>> {
+        let mut snapshot = Cursor::new(vec![]);
+        self.profile.write_to(&mut snapshot)?;
+        Ok(snapshot.into_inner())
+    }
+
+    pub fn from_snapshot(name: String, snapshot: &[u8]) -> Result<Self> {
+        Self::from_reader(name, Cursor::new(snapshot))
+    }
+
     pub fn can_create_new_file(name: String, directory: &Path) -> Result<()> {
         let path = directory.join(format!("{name}.goxlrMicProfile"));
         can_create_new_file(path)
@@ -777,8 +788,16 @@ impl MicProfileAdapter {
             false
         };
 
+        // The channel itself can also be muted, independently of the fader
+        let muted_by_channel =
+            profile.get_channel_mute(ChannelName::Mic) == Some(MuteFunction::All);
+
         // Check all the cases where we might be muted to all, and return.
-        if muted_to_all || (muted_to_x && mute_function == MuteFunction::All) || muted_by_fader {
+        if muted_to_all
+            || (muted_to_x && mute_function == MuteFunction::All)
+            || muted_by_fader
+            || muted_by_channel
+        {
             1
         } else {
             0
@@ -0,0 +1,51 @@
+use std::time::{Duration, Instant};
+
+// How long before a timed mute runs out that its button starts flashing
+const WARNING_PERIOD: Duration = Duration::from_secs(5);
+
+/*
+   A mute which lifts itself after a set amount of time. This only keeps track of the time,
+   the device checks these on every update tick and handles the actual muting and unmuting.
+*/
+pub struct MuteTimer {
+    ends: Instant,
+    announce: bool,
+    warned: bool,
+}
+
+impl MuteTimer {
+    pub fn new(duration: Duration, announce: bool) -> Self {
+        Self {
+            ends: Instant::now() + duration,
+            announce,
+            warned: false,
+        }
+    }
+
+    pub fn is_expired(&self) -> bool {
+        Instant::now() >= self.ends
+    }
+
+    pub fn is_warning(&self) -> bool {
+        self.ends.saturating_duration_since(Instant::now()) <= WARNING_PERIOD
+    }
+
+    pub fn announces(&self) -> bool {
+        self.announce
+    }
+
+    /// Returns true the first time this is called once the timer is in its warning period
+    pub fn check_warning(&mut self) -> bool {
+        if !self.warned && self.is_warning() {
+            self.warned = true;
+            return true;
+        }
+        false
+    }
+
+    /// Whole seconds left, rounded up so we don't report 0 while the mute is still active
+    pub fn remaining_secs(&self) -> u32 {
+        let remaining = self.ends.saturating_duration_since(Instant::now());
+        remaining.as_millis().div_ceil(1000) as u32
+    }
+}
@@ -1,23 +1,31 @@
-use crate::device::Device;
+use crate::app_rules::AppWatcher;
+use crate::device::{Device, RecoverableState};
 use crate::events::EventTriggers;
 use crate::files::extract_defaults;
 use crate::firmware::firmware_update::{
     FirmwareMessages, FirmwareRequest, FirmwareUpdateDevice, FirmwareUpdateSettings,
     do_firmware_update, start_firmware_update,
 };
+use crate::links::LinkChange;
 use crate::platform::{get_ui_app_path, has_autostart, set_autostart};
+use crate::scheduler::Scheduler;
 use crate::{
     FIRMWARE_PATHS, FileManager, PatchEvent, SYSTEM_LOCALE, SettingsHandle, Shutdown, VERSION,
 };
 use anyhow::{Result, anyhow};
+use chrono::Local;
 use enum_map::EnumMap;
 use goxlr_ipc::{
     Activation, ColourWay, DaemonCommand, DaemonConfig, DaemonStatus, DriverDetails, Files,
-    FirmwareSource, FirmwareStatus, GoXLRCommand, HardwareStatus, HttpSettings, Locale, PathTypes,
-    Paths, SampleFile, UpdateState, UsbProductInformation,
+    FirmwareSource, FirmwareStatus, GoXLRCommand, HardwareStatus, HttpSettings, LinkGroup, Locale,
+    MicLevelMeter, PathTypes, Paths, SampleFile, ScheduledAction, ScheduledTask, UpdateState,
+    UsbProductInformation,
 };
 use goxlr_types::{DeviceType, FirmwareDetails, VersionNumber};
 use goxlr_usb::device::base::GoXLRDevice;
+use goxlr_usb::device::capture::CapturingGoXLR;
+use goxlr_usb::device::emulated::{Emulation, create_emulated_device};
+use goxlr_usb::device::scheduler::ScheduledGoXLR;
 use goxlr_usb::device::{find_devices, from_device, get_version};
 use goxlr_usb::{PID_GOXLR_FULL, PID_GOXLR_MINI};
 use json_patch::diff;
@@ -26,24 +34,39 @@ use reqwest::{ClientBuilder, StatusCode};
 use std::collections::{BTreeMap, HashMap};
 use std::env;
 use std::path::PathBuf;
+use std::sync::{Arc, Weak};
 use std::time::{Duration, Instant};
-use tokio::sync::broadcast::Sender as BroadcastSender;
+use tokio::sync::broadcast::{Receiver as BroadcastReceiver, Sender as BroadcastSender};
 use tokio::sync::mpsc::{Receiver, Sender};
-use tokio::sync::{mpsc, oneshot};
+use tokio::sync::{broadcast, mpsc, oneshot};
 use tokio::time::sleep;
 use xmltree::Element;
 
 const IGNORE_DEVICE_DURATION: Duration = Duration::from_secs(10);
+
+// How long to hold on to the state of a disconnected device, in case it comes straight back.
+const RECOVERY_GRACE_PERIOD: Duration = Duration::from_secs(30);
 const APP_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+const APP_RULE_INTERVAL: Duration = Duration::from_secs(2);
+const SCHEDULE_INTERVAL: Duration = Duration::from_secs(5);
+
+// Mic Level Metering, the peak (and clip) indicators are held for a short time so they're
+// actually visible, and the rate is capped as every sample is a USB round trip.
+const METER_PEAK_HOLD: Duration = Duration::from_millis(1500);
+const METER_CLIP_LEVEL: f64 = -0.5;
+const METER_MAX_RATE: u8 = 30;
 
 // Adding a third entry has tripped enum_variant_names, I'll probably need to rename
 // RunDeviceCommand, but that'll need to be in a separate commit, for now, suppress.
 #[allow(clippy::enum_variant_names)]
 pub enum DeviceCommand {
     SendDaemonStatus(oneshot::Sender<DaemonStatus>),
+    SubscribeEvents(oneshot::Sender<(DaemonStatus, BroadcastReceiver<PatchEvent>)>),
     RunDaemonCommand(DaemonCommand, oneshot::Sender<Result<()>>),
     RunDeviceCommand(String, GoXLRCommand, oneshot::Sender<Result<()>>),
+    RunDeviceBatch(String, Vec<GoXLRCommand>, oneshot::Sender<Result<()>>),
     GetDeviceMicLevel(String, oneshot::Sender<Result<f64>>),
+    SubscribeMicLevel(String, u8, oneshot::Sender<Result<MicLevelSubscription>>),
     RunFirmwareUpdate(String, Option<PathBuf>, bool, oneshot::Sender<Result<()>>),
     ContinueFirmwareUpdate(String, oneshot::Sender<Result<()>>),
     ClearFirmwareState(String, oneshot::Sender<Result<()>>),
@@ -54,6 +77,84 @@ pub enum DeviceStateChange {
     Shutdown(bool),
     Sleep(oneshot::Sender<()>),
     Wake(oneshot::Sender<()>),
+    Lock,
+    Unlock,
+}
+
+// Held by a subscriber for as long as it wants meters, the rate it asked for stays in effect
+// until this is dropped.
+pub struct MicLevelSubscription {
+    pub receiver: BroadcastReceiver<MicLevelMeter>,
+    _handle: Arc<()>,
+}
+
+struct MicMeter {
+    sender: BroadcastSender<MicLevelMeter>,
+    subscribers: Vec<(Weak<()>, Duration)>,
+    next_sample: Instant,
+    peak: f64,
+    peak_until: Instant,
+    clipped_until: Instant,
+}
+
+impl MicMeter {
+    fn new() -> Self {
+        let (sender, _) = broadcast::channel(16);
+        let now = Instant::now();
+
+        Self {
+            sender,
+            subscribers: vec![],
+            next_sample: now,
+            peak: -72.2,
+            peak_until: now,
+            clipped_until: now,
+        }
+    }
+
+    fn subscribe(&mut self, interval: Duration) -> MicLevelSubscription {
+        let handle = Arc::new(());
+        self.subscribers.push((Arc::downgrade(&handle), interval));
+
+        MicLevelSubscription {
+            receiver: self.sender.subscribe(),
+            _handle: handle,
+        }
+    }
+
+    // Drops anyone who's gone away, returning false if there's nobody left
+    fn has_subscribers(&mut self) -> bool {
+        self.subscribers
+            .retain(|(handle, _)| handle.strong_count() > 0);
+        !self.subscribers.is_empty()
+    }
+
+    // If subscribers asked for different rates, we sample at the fastest of them
+    fn interval(&self) -> Duration {
+        self.subscribers
+            .iter()
+            .map(|(_, interval)| *interval)
+            .min()
+            .unwrap_or(Duration::from_secs(1))
+    }
+
+    fn update(&mut self, serial: &str, level: f64) -> MicLevelMeter {
+        let now = Instant::now();
+        if level >= self.peak || now >= self.peak_until {
+            self.peak = level;
+            self.peak_until = now + METER_PEAK_HOLD;
+        }
+        if level >= METER_CLIP_LEVEL {
+            self.clipped_until = now + METER_PEAK_HOLD;
+        }
+
+        MicLevelMeter {
+            serial: serial.to_string(),
+            level,
+            peak: self.peak,
+            clipped: now < self.clipped_until,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -69,6 +170,7 @@ pub type DeviceReceiver = Receiver<DeviceCommand>;
 #[allow(clippy::too_many_arguments)]
 pub async fn spawn_usb_handler(
     mut command_rx: DeviceReceiver,
+    command_tx: DeviceSender,
     mut file_rx: Receiver<PathTypes>,
     mut device_state_rx: Receiver<DeviceStateChange>,
     broadcast_tx: BroadcastSender<PatchEvent>,
@@ -78,6 +180,8 @@ pub async fn spawn_usb_handler(
     settings: SettingsHandle,
     http_settings: HttpSettings,
     mut file_manager: FileManager,
+    emulated_devices: Vec<Emulation>,
+    capture_directory: Option<PathBuf>,
 ) {
     let mut firmware_version = None;
 
@@ -106,6 +210,17 @@ pub async fn spawn_usb_handler(
     let update_sleep = sleep(update_duration);
     tokio::pin!(update_sleep);
 
+    // Timer for sending any USB writes which have been held back to be coalesced, only runs
+    // while a device has something waiting..
+    let flush_duration = Duration::from_millis(10);
+    let flush_sleep = sleep(flush_duration);
+    tokio::pin!(flush_sleep);
+
+    // Timer for sampling the Mic Level, only runs while something is subscribed..
+    let meter_duration = Duration::from_millis(10);
+    let meter_sleep = sleep(meter_duration);
+    tokio::pin!(meter_sleep);
+
     // Timer for checking whether the UI App has appeared
     let mut app_check: Option<String> = None;
     get_app_path(&mut app_check);
@@ -114,6 +229,16 @@ pub async fn spawn_usb_handler(
     let app_sleep = sleep(app_duration);
     tokio::pin!(app_sleep);
 
+    // Timer for checking running applications against App Rules
+    let mut app_watcher = AppWatcher::default();
+    let app_rule_sleep = sleep(APP_RULE_INTERVAL);
+    tokio::pin!(app_rule_sleep);
+
+    // Timer for running Scheduled Tasks
+    let mut scheduler = Scheduler::default();
+    let schedule_sleep = sleep(SCHEDULE_INTERVAL);
+    tokio::pin!(schedule_sleep);
+
     // Get the Driver Type and Details..
     let (interface, version) = get_version();
     let mut driver_interface = DriverDetails { interface, version };
@@ -123,6 +248,23 @@ pub async fn spawn_usb_handler(
     let mut devices_firmware: HashMap<String, FirmwareUpdateState> = HashMap::new();
     let mut ignore_list = HashMap::new();
 
+    // Devices which have recently disconnected, and when their state should be discarded..
+    let mut recoverable: HashMap<String, (Instant, RecoverableState)> = HashMap::new();
+
+    // Active Mic Level Meters, by device serial..
+    let mut mic_meters: HashMap<String, MicMeter> = HashMap::new();
+
+    // Emulated devices are never 'found' on the bus, so we'll keep a list of them here and offer
+    // them up alongside any real devices during detection.
+    let emulated_devices: Vec<GoXLRDevice> = emulated_devices
+        .into_iter()
+        .enumerate()
+        .map(|(index, emulation)| {
+            info!("Creating Emulated GoXLR {:?} ({})", emulation, index);
+            create_emulated_device(emulation, index as u8)
+        })
+        .collect();
+
     let mut files = get_files(&mut file_manager, &settings).await;
     let mut daemon_status = get_daemon_status(
         &devices,
@@ -141,6 +283,7 @@ pub async fn spawn_usb_handler(
 
     loop {
         let mut change_found = false;
+        let pending_writes = devices.values().any(|device| device.has_pending_writes());
         tokio::select! {
             Some(version) = firmware_receiver.recv() => {
                 firmware_version = Some(version);
@@ -233,7 +376,15 @@ pub async fn spawn_usb_handler(
                 }
             }
             () = &mut detection_sleep => {
-                if let Some(device) = find_new_device(&daemon_status, &ignore_list) {
+                recoverable.retain(|serial, (expires, _)| {
+                    if *expires > Instant::now() {
+                        return true;
+                    }
+                    info!("[{}] Device did not return, discarding its previous state", serial);
+                    false
+                });
+
+                if let Some(device) = find_new_device(&daemon_status, &ignore_list, &emulated_devices) {
                     let existing_serials: Vec<String> = get_all_serials(&devices);
                     let bus_number = device.bus_number();
                     let address = device.address();
@@ -243,7 +394,7 @@ pub async fn spawn_usb_handler(
                         device_identifier = Some(identifier.clone());
                     }
 
-                    match load_device(device, existing_serials, disconnect_sender.clone(), event_sender.clone(), global_tx.clone(), &settings).await {
+                    match load_device(device, existing_serials, disconnect_sender.clone(), event_sender.clone(), global_tx.clone(), &settings, &capture_directory, &mut recoverable).await {
                         Ok(device) => {
                             let serial = String::from(device.serial());
 
@@ -273,6 +424,7 @@ pub async fn spawn_usb_handler(
                 detection_sleep.as_mut().reset(tokio::time::Instant::now() + detection_duration);
             },
             () = &mut update_sleep => {
+                let mut lost = vec![];
                 for device in devices.values_mut() {
                     let updated = device.update_state().await;
 
@@ -282,19 +434,86 @@ pub async fn spawn_usb_handler(
 
                     if let Err(error) = updated {
                         warn!("Error Received from {} while updating state: {}", device.serial(), error);
+                        if !device.is_connected() {
+                            lost.push(device.serial().to_owned());
+                        }
                     }
                 }
+                for serial in lost {
+                    info!("[{}] Device Lost", serial);
+                    remove_device(&serial, &mut devices, &mut recoverable);
+                    change_found = true;
+                }
                 update_sleep.as_mut().reset(tokio::time::Instant::now() + update_duration);
             },
+            () = &mut flush_sleep, if pending_writes => {
+                let mut lost = vec![];
+                for device in devices.values_mut() {
+                    if let Err(error) = device.flush_writes() {
+                        warn!("Error Received from {} while flushing writes: {}", device.serial(), error);
+                        if !device.is_connected() {
+                            lost.push(device.serial().to_owned());
+                        }
+                    }
+                }
+                for serial in lost {
+                    info!("[{}] Device Lost", serial);
+                    remove_device(&serial, &mut devices, &mut recoverable);
+                    change_found = true;
+                }
+                flush_sleep.as_mut().reset(tokio::time::Instant::now() + flush_duration);
+            },
+            () = &mut meter_sleep, if !mic_meters.is_empty() => {
+                // Stop sampling as soon as nobody is listening (or the device has gone)..
+                mic_meters.retain(|serial, meter| {
+                    meter.has_subscribers() && devices.contains_key(serial)
+                });
+
+                for (serial, meter) in mic_meters.iter_mut() {
+                    if meter.next_sample > Instant::now() {
+                        continue;
+                    }
+                    meter.next_sample = Instant::now() + meter.interval();
+
+                    if let Some(device) = devices.get_mut(serial) {
+                        match device.get_mic_level().await {
+                            Ok(level) => {
+                                let update = meter.update(serial, level);
+                                let _ = meter.sender.send(update);
+                            }
+                            Err(error) => warn!("Unable to sample Mic Level for {}: {}", serial, error),
+                        }
+                    }
+                }
+                meter_sleep.as_mut().reset(tokio::time::Instant::now() + meter_duration);
+            },
             () = &mut app_sleep => {
                 if get_app_path(&mut app_check) {
                     change_found = true;
                 }
                 app_sleep.as_mut().reset(tokio::time::Instant::now() + APP_CHECK_INTERVAL);
             },
+            () = &mut app_rule_sleep => {
+                app_watcher.invalidate();
+                for device in devices.values_mut() {
+                    if device.update_app_rules(&mut app_watcher).await {
+                        change_found = true;
+                    }
+                }
+                app_rule_sleep.as_mut().reset(tokio::time::Instant::now() + APP_RULE_INTERVAL);
+            },
+            () = &mut schedule_sleep => {
+                let schedule = settings.get_schedule().await;
+                let due = scheduler.check(&schedule, Local::now());
+                if !due.is_empty() {
+                    run_scheduled_tasks(due, &schedule, &mut devices).await;
+                    change_found = true;
+                }
+                schedule_sleep.as_mut().reset(tokio::time::Instant::now() + SCHEDULE_INTERVAL);
+            },
             Some(serial) = disconnect_receiver.recv() => {
                 info!("[{}] Device Disconnected", serial);
-                devices.remove(&serial);
+                remove_device(&serial, &mut devices, &mut recoverable);
 
                 // If this device was actively doing a firmware update that's not complete, we should scream
                 // INCREDIBLY loudly (in the logs).. We will keep this device around though, the error will
@@ -317,6 +536,11 @@ pub async fn spawn_usb_handler(
 
                     if let Err(error) = result {
                         warn!("Error Received from {}: {}", device.serial(), error);
+                        if !device.is_connected() {
+                            info!("[{}] Device Lost", serial);
+                            remove_device(&serial, &mut devices, &mut recoverable);
+                            change_found = true;
+                        }
                     }
                 } else {
                     warn!("Cannot find registered device with serial: {}", &serial);
@@ -341,6 +565,7 @@ pub async fn spawn_usb_handler(
                     },
                     DeviceStateChange::Sleep(sender) => {
                         debug!("Received Sleep Notification");
+                        scheduler.sleep();
                         for device in devices.values_mut() {
                             device.sleep().await;
                         }
@@ -353,10 +578,29 @@ pub async fn spawn_usb_handler(
                         }
                         let _ = sender.send(());
 
+                        // Catch up on anything which should have run while we were asleep
+                        let schedule = settings.get_schedule().await;
+                        let missed = scheduler.wake(&schedule, Local::now());
+                        run_scheduled_tasks(missed, &schedule, &mut devices).await;
+
                         // We'll set this regardless and refresh the Status object, this
                         // allows the UI to update when waking up.
                         change_found = true;
                     }
+                    DeviceStateChange::Lock => {
+                        debug!("Received Lock Notification");
+                        for device in devices.values_mut() {
+                            device.lock().await;
+                        }
+                        change_found = true;
+                    }
+                    DeviceStateChange::Unlock => {
+                        debug!("Received Unlock Notification");
+                        for device in devices.values_mut() {
+                            device.unlock().await;
+                        }
+                        change_found = true;
+                    }
                 }
 
 
@@ -371,6 +615,12 @@ pub async fn spawn_usb_handler(
                         let _ = sender.send(daemon_status.clone());
                     }
 
+                    DeviceCommand::SubscribeEvents(sender) => {
+                        // Subscribing here means no patch can land between the status and the
+                        // receiver, so every patch received applies cleanly on top of it.
+                        let _ = sender.send((daemon_status.clone(), broadcast_tx.subscribe()));
+                    }
+
                     DeviceCommand::RunDaemonCommand(command, sender) => {
                         match command {
                             DaemonCommand::StopDaemon => {
@@ -490,6 +740,82 @@ pub async fn spawn_usb_handler(
                                 change_found = true;
                                 let _ = sender.send(Ok(()));
                             }
+                            DaemonCommand::SetAppRule(serial, name, rule) => {
+                                let mut rules = settings.get_device_app_rules(&serial).await;
+                                rules.insert(name, rule);
+                                settings.set_device_app_rules(&serial, rules).await;
+                                settings.save().await;
+
+                                change_found = true;
+                                let _ = sender.send(Ok(()));
+                            }
+                            DaemonCommand::DeleteAppRule(serial, name) => {
+                                let mut rules = settings.get_device_app_rules(&serial).await;
+                                if rules.remove(&name).is_none() {
+                                    let error = anyhow!("App Rule {} does not exist", name);
+                                    let _ = sender.send(Err(error));
+                                } else {
+                                    settings.set_device_app_rules(&serial, rules).await;
+                                    settings.save().await;
+
+                                    change_found = true;
+                                    let _ = sender.send(Ok(()));
+                                }
+                            }
+                            DaemonCommand::SetScheduledTask(name, task) => {
+                                if task.hour > 23 || task.minute > 59 {
+                                    let error = anyhow!("Invalid Time {}:{}", task.hour, task.minute);
+                                    let _ = sender.send(Err(error));
+                                } else {
+                                    let mut schedule = settings.get_schedule().await;
+                                    schedule.insert(name, task);
+                                    settings.set_schedule(schedule).await;
+                                    settings.save().await;
+
+                                    change_found = true;
+                                    let _ = sender.send(Ok(()));
+                                }
+                            }
+                            DaemonCommand::SetLinkGroup(name, group) => {
+                                if group.serials.len() < 2 {
+                                    let error = anyhow!("A Link Group needs at least two devices");
+                                    let _ = sender.send(Err(error));
+                                } else {
+                                    let mut groups = settings.get_link_groups().await;
+                                    groups.insert(name, group);
+                                    settings.set_link_groups(groups).await;
+                                    settings.save().await;
+
+                                    change_found = true;
+                                    let _ = sender.send(Ok(()));
+                                }
+                            }
+                            DaemonCommand::DeleteLinkGroup(name) => {
+                                let mut groups = settings.get_link_groups().await;
+                                if groups.remove(&name).is_none() {
+                                    let error = anyhow!("Link Group {} does not exist", name);
+                                    let _ = sender.send(Err(error));
+                                } else {
+                                    settings.set_link_groups(groups).await;
+                                    settings.save().await;
+
+                                    change_found = true;
+                                    let _ = sender.send(Ok(()));
+                                }
+                            }
+                            DaemonCommand::DeleteScheduledTask(name) => {
+                                let mut schedule = settings.get_schedule().await;
+                                if schedule.remove(&name).is_none() {
+                                    let error = anyhow!("Scheduled Task {} does not exist", name);
+                                    let _ = sender.send(Err(error));
+                                } else {
+                                    settings.set_schedule(schedule).await;
+                                    settings.save().await;
+
+                                    change_found = true;
+                                    let _ = sender.send(Ok(()));
+                                }
+                            }
                         }
                     },
 
@@ -511,6 +837,19 @@ pub async fn spawn_usb_handler(
                         }
                     },
 
+                    DeviceCommand::RunDeviceBatch(serial, commands, sender) => {
+                        if let Some(device) = devices.get_mut(&serial) {
+                            let result = device.perform_batch(commands).await;
+                            if let Err(error) = &result {
+                                warn!("Error Executing Batch: {}", error);
+                            }
+                            let _ = sender.send(result);
+                            change_found = true;
+                        } else {
+                            let _ = sender.send(Err(anyhow!("Device {} is not connected", serial)));
+                        }
+                    },
+
                     DeviceCommand::GetDeviceMicLevel(serial, sender) => {
                         if let Some(device) = devices.get_mut(&serial) {
                             let _ = sender.send(device.get_mic_level().await);
@@ -519,6 +858,16 @@ pub async fn spawn_usb_handler(
                         }
                     },
 
+                    DeviceCommand::SubscribeMicLevel(serial, rate, sender) => {
+                        if devices.contains_key(&serial) {
+                            let interval = Duration::from_secs(1) / rate.clamp(1, METER_MAX_RATE) as u32;
+                            let meter = mic_meters.entry(serial).or_insert_with(MicMeter::new);
+                            let _ = sender.send(Ok(meter.subscribe(interval)));
+                        } else {
+                            let _ = sender.send(Err(anyhow!("Device {} is not connected", serial)));
+                        }
+                    },
+
                     DeviceCommand::RunFirmwareUpdate(serial, file, force, sender) => {
                         let mut start_update = true;
                         if let Some(device) = devices.get_mut(&serial) {
@@ -622,6 +971,30 @@ pub async fn spawn_usb_handler(
         }
 
         if change_found {
+            // Give any rules a chance to act on the change before the status is built
+            let mut daemon_commands = vec![];
+            for device in devices.values_mut() {
+                daemon_commands.extend(device.evaluate_rules().await);
+            }
+
+            // Daemon commands go back through the queue as if they came from a client, this
+            // needs to happen elsewhere as we're the ones who'd be receiving them.
+            for command in daemon_commands {
+                let command_tx = command_tx.clone();
+                tokio::spawn(async move {
+                    let (tx, rx) = oneshot::channel();
+                    let _ = command_tx
+                        .send(DeviceCommand::RunDaemonCommand(command, tx))
+                        .await;
+                    if let Ok(Err(error)) = rx.await {
+                        warn!("Error Running Rule Daemon Command: {}", error);
+                    }
+                });
+            }
+
+            let link_groups = settings.get_link_groups().await;
+            sync_linked_devices(&link_groups, &mut devices).await;
+
             let new_status = get_daemon_status(
                 &devices,
                 &settings,
@@ -696,6 +1069,8 @@ async fn get_daemon_status(
             logs_directory: settings.get_log_directory().await,
         },
         files,
+        schedule: settings.get_schedule().await,
+        link_groups: settings.get_link_groups().await,
         ..Default::default()
     };
 
@@ -714,6 +1089,44 @@ async fn get_daemon_status(
     status
 }
 
+// Replays changes made on any device on the other members of its link groups
+async fn sync_linked_devices(
+    groups: &BTreeMap<String, LinkGroup>,
+    devices: &mut HashMap<String, Device<'_>>,
+) {
+    // Collect everything first, so changes being replayed don't get picked up as new ones.
+    let mut changes = vec![];
+    for (serial, device) in devices.iter_mut() {
+        let device_changes = device.take_link_changes();
+        if !device_changes.is_empty() {
+            changes.push((serial.clone(), device_changes));
+        }
+    }
+
+    for (source, source_changes) in changes {
+        for group in groups.values() {
+            if !group.serials.contains(&source) {
+                continue;
+            }
+
+            let linked: Vec<LinkChange> = source_changes
+                .iter()
+                .filter(|(category, _)| category.is_linked(group))
+                .map(|(_, change)| change.clone())
+                .collect();
+            if linked.is_empty() {
+                continue;
+            }
+
+            for serial in group.serials.iter().filter(|serial| *serial != &source) {
+                if let Some(device) = devices.get_mut(serial) {
+                    device.apply_linked_changes(linked.clone()).await;
+                }
+            }
+        }
+    }
+}
+
 #[allow(const_item_mutation)]
 fn get_app_path(app_check: &mut Option<String>) -> bool {
     if let Some(path) = get_ui_app_path() {
@@ -744,6 +1157,40 @@ fn get_app_path(app_check: &mut Option<String>) -> bool {
     }
 }
 
+async fn run_scheduled_tasks(
+    names: Vec<String>,
+    schedule: &BTreeMap<String, ScheduledTask>,
+    devices: &mut HashMap<String, Device<'_>>,
+) {
+    for name in names {
+        let Some(task) = schedule.get(&name) else {
+            continue;
+        };
+
+        let commands = match &task.action {
+            ScheduledAction::Commands(commands) => commands.clone(),
+            ScheduledAction::LoadProfile(profile) => {
+                vec![GoXLRCommand::LoadProfile(profile.clone(), true)]
+            }
+            ScheduledAction::LoadMicProfile(profile) => {
+                vec![GoXLRCommand::LoadMicProfile(profile.clone(), true)]
+            }
+        };
+
+        debug!("Running Scheduled Task: {}", name);
+        for (serial, device) in devices.iter_mut() {
+            if task.serial.as_ref().is_some_and(|target| target != serial) {
+                continue;
+            }
+            for command in &commands {
+                if let Err(error) = device.perform_command(command.clone()).await {
+                    warn!("Error Running Scheduled Task {}: {}", name, error);
+                }
+            }
+        }
+    }
+}
+
 async fn get_sample_files(
     file_manager: &mut FileManager,
     settings: &SettingsHandle,
@@ -824,10 +1271,12 @@ async fn update_files(
 fn find_new_device(
     current_status: &DaemonStatus,
     devices_to_ignore: &HashMap<(u8, u8, Option<String>), Instant>,
+    emulated_devices: &[GoXLRDevice],
 ) -> Option<GoXLRDevice> {
     let now = Instant::now();
 
-    let goxlr_devices = find_devices();
+    let mut goxlr_devices = find_devices();
+    goxlr_devices.extend_from_slice(emulated_devices);
     goxlr_devices.into_iter().find(|device| {
         // Check the Mixers on the existing DaemonStatus..
         !current_status.mixers.values().any(|d| {
@@ -853,6 +1302,26 @@ fn find_new_device(
     })
 }
 
+// Drops a device which has gone away. Its in-memory state is held on to for a while, if this was
+// a transient USB failure the device will re-enumerate shortly and we can restore it.
+fn remove_device(
+    serial: &str,
+    devices: &mut HashMap<String, Device>,
+    recoverable: &mut HashMap<String, (Instant, RecoverableState)>,
+) {
+    let Some(device) = devices.remove(serial) else {
+        return;
+    };
+
+    match device.into_recoverable_state() {
+        Ok(state) => {
+            let expires = Instant::now() + RECOVERY_GRACE_PERIOD;
+            recoverable.insert(serial.to_owned(), (expires, state));
+        }
+        Err(error) => warn!("[{}] Unable to keep previous state: {}", serial, error),
+    }
+}
+
 fn get_all_serials(existing_devices: &HashMap<String, Device>) -> Vec<String> {
     let mut serials: Vec<String> = vec![];
 
@@ -863,17 +1332,30 @@ fn get_all_serials(existing_devices: &HashMap<String, Device>) -> Vec<String> {
     serials
 }
 
-async fn load_device(
+async fn load_device<'a>(
     device: GoXLRDevice,
     existing_serials: Vec<String>,
     disconnect_sender: Sender<String>,
     event_sender: Sender<String>,
     global_events: Sender<EventTriggers>,
-    settings: &SettingsHandle,
-) -> Result<Device<'_>> {
+    settings: &'a SettingsHandle,
+    capture_directory: &Option<PathBuf>,
+    recoverable: &mut HashMap<String, (Instant, RecoverableState)>,
+) -> Result<Device<'a>> {
     let device_copy = device.clone();
 
     let mut handled_device = from_device(device, disconnect_sender, event_sender, false)?;
+    if let Some(directory) = capture_directory {
+        let file_date = Local::now().format("%Y-%m-%dT%H%M%S").to_string();
+        let file_name = format!(
+            "capture-{}-{}-{}.jsonl",
+            device_copy.bus_number(),
+            device_copy.address(),
+            file_date
+        );
+        handled_device = CapturingGoXLR::wrap(handled_device, &directory.join(file_name))?;
+    }
+    handled_device = ScheduledGoXLR::wrap(handled_device);
     let descriptor = handled_device.get_descriptor()?;
 
     let device_type = match descriptor.product_id() {
@@ -923,7 +1405,17 @@ async fn load_device(
         colour_way,
         usb_device,
     };
-    let device = Device::new(handled_device, hardware, settings, global_events).await?;
+    // The previous state is only discarded once the device has successfully loaded with it, so a
+    // failed attempt can be retried while the grace period lasts.
+    let recovered = recoverable.get(&serial_number).map(|(_, state)| {
+        info!(
+            "[{}] Device Reconnected, restoring previous state",
+            serial_number
+        );
+        state
+    });
+    let device = Device::new(handled_device, hardware, settings, global_events, recovered).await?;
+    recoverable.remove(&serial_number);
     settings
         .set_device_profile_name(&serial_number, device.profile().name())
         .await;
@@ -86,6 +86,17 @@ impl ProfileAdapter {
         Ok(Self { name, profile })
     }
 
+    // An in-memory copy of the profile, so changes can be rolled back without touching the disk.
+    pub fn snapshot(&mut self) -> Result<Vec<u8>> {
+        let mut snapshot = Cursor::new(vec![]);
+        self.profile.write_to(&mut snapshot)?;
+        Ok(snapshot.into_inner())
+    }
+
+    pub fn from_snapshot(name: String, snapshot: &[u8]) -> Result<Self> {
+        Self::from_reader(name, Cursor::new(snapshot))
+    }
+
     pub fn can_create_new_file(name: String, directory: &Path) -> Result<()> {
         let path = directory.join(format!("{name}.goxlr"));
         can_create_new_file(path)
@@ -480,6 +491,27 @@ impl ProfileAdapter {
             .set_channel_volume(standard_to_profile_channel(channel), volume)
     }
 
+    pub fn get_channel_mute(&self, channel: ChannelName) -> Option<MuteFunction> {
+        self.profile
+            .settings()
+            .mixer()
+            .channel_mute(standard_to_profile_channel(channel))
+    }
+
+    pub fn get_channel_mutes_ipc(&self) -> EnumMap<ChannelName, Option<BasicMuteFunction>> {
+        EnumMap::from_fn(|channel| {
+            self.get_channel_mute(channel)
+                .map(profile_to_standard_mute_function)
+        })
+    }
+
+    pub fn set_channel_mute(&mut self, channel: ChannelName, function: Option<BasicMuteFunction>) {
+        self.profile.settings_mut().mixer_mut().set_channel_mute(
+            standard_to_profile_channel(channel),
+            function.map(standard_to_profile_mute_function),
+        );
+    }
+
     pub fn get_colour_map(&self, use_format_1_3_40: bool, blank_mute: bool) -> [u8; 520] {
         let mut colour_array = [0; 520];
 
@@ -987,6 +1019,29 @@ impl ProfileAdapter {
     pub fn set_mute_button_behaviour(&mut self, fader: FaderName, behaviour: BasicMuteFunction) {
         let mute_config = self.get_mute_button_mut(fader);
         mute_config.set_mute_function(standard_to_profile_mute_function(behaviour));
+        mute_config.set_mute_targets(None);
+    }
+
+    pub fn get_mute_button_targets(&self, fader: FaderName) -> Option<Vec<OutputDevice>> {
+        let targets = self.get_mute_button(fader).mute_targets()?;
+        Some(
+            targets
+                .iter()
+                .map(|t| profile_to_standard_output(*t))
+                .collect(),
+        )
+    }
+
+    pub fn set_mute_button_targets(&mut self, fader: FaderName, targets: Vec<OutputDevice>) {
+        let mute_config = self.get_mute_button_mut(fader);
+        if targets.is_empty() {
+            mute_config.set_mute_targets(None);
+            return;
+        }
+
+        mute_config.set_mute_function(nearest_mute_function(&targets));
+        let targets = targets.into_iter().map(standard_output_to_profile);
+        mute_config.set_mute_targets(Some(targets.collect()));
     }
 
     pub fn get_mute_button_state(&self, fader: FaderName) -> (bool, bool, MuteFunction) {
@@ -1038,6 +1093,29 @@ impl ProfileAdapter {
     pub fn set_chat_mute_button_behaviour(&mut self, behaviour: BasicMuteFunction) {
         let mute_config = self.get_chat_mute_button_mut();
         mute_config.set_cough_mute_source(standard_to_profile_mute_function(behaviour));
+        mute_config.set_cough_mute_targets(None);
+    }
+
+    pub fn get_chat_mute_button_targets(&self) -> Option<Vec<OutputDevice>> {
+        let targets = self.get_chat_mute_button().cough_mute_targets()?;
+        Some(
+            targets
+                .iter()
+                .map(|t| profile_to_standard_output(*t))
+                .collect(),
+        )
+    }
+
+    pub fn set_chat_mute_button_targets(&mut self, targets: Vec<OutputDevice>) {
+        let mute_config = self.get_chat_mute_button_mut();
+        if targets.is_empty() {
+            mute_config.set_cough_mute_targets(None);
+            return;
+        }
+
+        mute_config.set_cough_mute_source(nearest_mute_function(&targets));
+        let targets = targets.into_iter().map(standard_output_to_profile);
+        mute_config.set_cough_mute_targets(Some(targets.collect()));
     }
 
     pub fn set_chat_mute_button_is_held(&mut self, is_hold: bool) {
@@ -1122,7 +1200,9 @@ impl ProfileAdapter {
             mute_type: profile_to_standard_mute_function(
                 *self.profile.settings().mute_chat().cough_mute_source(),
             ),
+            mute_targets: self.get_chat_mute_button_targets(),
             state: mic_state,
+            mute_timer: None,
         }
     }
 
@@ -1237,7 +1317,7 @@ impl ProfileAdapter {
     }
 
     /** Effects Bank Behaviours **/
-    pub fn get_active_effect_bank(&mut self) -> EffectBankPresets {
+    pub fn get_active_effect_bank(&self) -> EffectBankPresets {
         let current = self.profile.settings().context().selected_effects();
         profile_to_standard_preset(current)
     }
@@ -2601,6 +2681,29 @@ fn standard_output_to_profile(value: OutputDevice) -> OutputChannels {
     }
 }
 
+// The profile still needs a Mute Function for custom targets, so pick the closest one, with the
+// streams taking priority as they're the outputs people most need to stay muted.
+fn nearest_mute_function(targets: &[OutputDevice]) -> MuteFunction {
+    let stream = targets.contains(&OutputDevice::BroadcastMix);
+    let stream2 = targets.contains(&OutputDevice::StreamMix2);
+
+    if stream && stream2 {
+        MuteFunction::ToStreams
+    } else if stream {
+        MuteFunction::ToStream
+    } else if stream2 {
+        MuteFunction::ToStream2
+    } else if targets.contains(&OutputDevice::ChatMic) {
+        MuteFunction::ToVoiceChat
+    } else if targets.contains(&OutputDevice::Headphones) {
+        MuteFunction::ToPhones
+    } else if targets.contains(&OutputDevice::LineOut) {
+        MuteFunction::ToLineOut
+    } else {
+        MuteFunction::ToStream
+    }
+}
+
 fn profile_to_standard_mute_function(value: MuteFunction) -> BasicMuteFunction {
     match value {
         MuteFunction::All => BasicMuteFunction::All,
@@ -3351,6 +3454,35 @@ pub fn usb_to_standard_button(source: Buttons) -> Button {
     }
 }
 
+pub fn standard_to_usb_button(source: Button) -> Buttons {
+    match source {
+        Button::Fader1Mute => Buttons::Fader1Mute,
+        Button::Fader2Mute => Buttons::Fader2Mute,
+        Button::Fader3Mute => Buttons::Fader3Mute,
+        Button::Fader4Mute => Buttons::Fader4Mute,
+        Button::Bleep => Buttons::Bleep,
+        Button::Cough => Buttons::MicrophoneMute,
+        Button::EffectSelect1 => Buttons::EffectSelect1,
+        Button::EffectSelect2 => Buttons::EffectSelect2,
+        Button::EffectSelect3 => Buttons::EffectSelect3,
+        Button::EffectSelect4 => Buttons::EffectSelect4,
+        Button::EffectSelect5 => Buttons::EffectSelect5,
+        Button::EffectSelect6 => Buttons::EffectSelect6,
+        Button::EffectFx => Buttons::EffectFx,
+        Button::EffectMegaphone => Buttons::EffectMegaphone,
+        Button::EffectRobot => Buttons::EffectRobot,
+        Button::EffectHardTune => Buttons::EffectHardTune,
+        Button::SamplerSelectA => Buttons::SamplerSelectA,
+        Button::SamplerSelectB => Buttons::SamplerSelectB,
+        Button::SamplerSelectC => Buttons::SamplerSelectC,
+        Button::SamplerTopLeft => Buttons::SamplerTopLeft,
+        Button::SamplerTopRight => Buttons::SamplerTopRight,
+        Button::SamplerBottomLeft => Buttons::SamplerBottomLeft,
+        Button::SamplerBottomRight => Buttons::SamplerBottomRight,
+        Button::SamplerClear => Buttons::SamplerClear,
+    }
+}
+
 pub fn version_newer_or_equal_to(version: &VersionNumber, comparison: VersionNumber) -> bool {
     version >= &comparison
 }
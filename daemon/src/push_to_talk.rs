@@ -0,0 +1,98 @@
+use goxlr_ipc::PushToTalk;
+use std::time::{Duration, Instant};
+
+// How quickly the button needs to be pressed again after a release to latch the mic open
+const LATCH_WINDOW: Duration = Duration::from_millis(300);
+
+/*
+   Push to Talk inverts the cough button, the mic is muted unless the button is held. This only
+   tracks whether the mic should currently be open, the actual muting is handled by the device
+   so it goes through the same path as the cough button (and respects its mute function).
+*/
+pub struct PushToTalkEngine {
+    config: PushToTalk,
+    held: bool,
+    latched: bool,
+    last_release: Option<Instant>,
+    mute_at: Option<Instant>,
+}
+
+impl PushToTalkEngine {
+    pub fn new(config: PushToTalk) -> Self {
+        Self {
+            config,
+            held: false,
+            latched: false,
+            last_release: None,
+            mute_at: None,
+        }
+    }
+
+    pub fn config(&self) -> PushToTalk {
+        self.config
+    }
+
+    pub fn set_config(&mut self, config: PushToTalk) {
+        if !config.latch {
+            self.latched = false;
+        }
+        self.config = config;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    /// Returns true if the mic should currently be unmuted
+    pub fn is_open(&self) -> bool {
+        self.held || self.latched || self.mute_at.is_some()
+    }
+
+    pub fn press(&mut self) {
+        self.held = true;
+        self.mute_at = None;
+
+        // Pressing while latched drops the latch, the mic then closes as normal on release.
+        if self.latched {
+            self.latched = false;
+            self.last_release = None;
+            return;
+        }
+
+        if self.config.latch
+            && let Some(released) = self.last_release.take()
+            && released.elapsed() <= LATCH_WINDOW
+        {
+            self.latched = true;
+        }
+    }
+
+    pub fn release(&mut self) {
+        self.held = false;
+        if self.latched {
+            return;
+        }
+
+        let now = Instant::now();
+        self.last_release = Some(now);
+        self.mute_at = Some(now + Duration::from_millis(self.config.release_tail.into()));
+    }
+
+    /// Returns true once the release tail has passed, and the mic should be muted again
+    pub fn poll(&mut self) -> bool {
+        if let Some(mute_at) = self.mute_at
+            && Instant::now() >= mute_at
+        {
+            self.mute_at = None;
+            return true;
+        }
+        false
+    }
+
+    pub fn reset(&mut self) {
+        self.held = false;
+        self.latched = false;
+        self.last_release = None;
+        self.mute_at = None;
+    }
+}
@@ -0,0 +1,60 @@
+use goxlr_types::VolumeCurve;
+use std::time::{Duration, Instant};
+
+// How far a fader can report outside of a ramp before we assume a human has moved it
+const PATH_TOLERANCE: u8 = 5;
+
+/*
+   Moves a single volume to a target over a duration. Like scene fades, each update tick
+   works out where the volume should be from the time elapsed, so the ramp always finishes
+   on time regardless of how regularly it's polled.
+*/
+pub struct VolumeRamp {
+    started: Instant,
+    duration: Duration,
+    curve: VolumeCurve,
+    from: u8,
+    to: u8,
+}
+
+impl VolumeRamp {
+    pub fn new(from: u8, to: u8, duration: Duration, curve: VolumeCurve) -> Self {
+        Self {
+            started: Instant::now(),
+            duration,
+            curve,
+            from,
+            to,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.started.elapsed() >= self.duration
+    }
+
+    /// Returns true if a volume lies between the start and end of the ramp (with some slack)
+    pub fn is_on_path(&self, volume: u8) -> bool {
+        let min = self.from.min(self.to).saturating_sub(PATH_TOLERANCE);
+        let max = self.from.max(self.to).saturating_add(PATH_TOLERANCE);
+        (min..=max).contains(&volume)
+    }
+
+    /// The volume the ramp should currently be at
+    pub fn volume(&self) -> u8 {
+        let progress = if self.duration.is_zero() {
+            1.0
+        } else {
+            (self.started.elapsed().as_secs_f64() / self.duration.as_secs_f64()).min(1.0)
+        };
+
+        let position = match self.curve {
+            VolumeCurve::Linear => progress,
+            VolumeCurve::Logarithmic => (1.0 + 9.0 * progress).log10(),
+            VolumeCurve::SCurve => progress * progress * (3.0 - 2.0 * progress),
+        };
+
+        let from = f64::from(self.from);
+        let to = f64::from(self.to);
+        (from + (to - from) * position).round() as u8
+    }
+}
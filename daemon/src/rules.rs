@@ -0,0 +1,67 @@
+use enum_map::EnumMap;
+use goxlr_ipc::{RuleEffect, RuleTrigger};
+use goxlr_types::{ChannelName, MuteState, SampleBank, SampleButtons};
+
+/*
+   Rules don't look at the device directly, instead the device takes a snapshot of everything a
+   rule can trigger on whenever its state changes, and the triggers are checked by comparing
+   that against the previous snapshot.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleState {
+    pub profile_name: String,
+    pub profile_loads: u32,
+    pub mute_states: EnumMap<ChannelName, MuteState>,
+    pub volumes: EnumMap<ChannelName, u8>,
+    pub samples_playing: EnumMap<SampleBank, EnumMap<SampleButtons, bool>>,
+    pub effects: EnumMap<RuleEffect, bool>,
+}
+
+// Where a channel can be muted in more than one way, the rules see the strongest of them
+pub fn most_muted(a: MuteState, b: MuteState) -> MuteState {
+    match (a, b) {
+        (MuteState::MutedToAll, _) | (_, MuteState::MutedToAll) => MuteState::MutedToAll,
+        (MuteState::MutedToX, _) | (_, MuteState::MutedToX) => MuteState::MutedToX,
+        _ => MuteState::Unmuted,
+    }
+}
+
+/// Returns true if the trigger has been hit moving from the previous state to the current one,
+/// if there is no previous state the device has only just connected.
+pub fn is_triggered(
+    trigger: &RuleTrigger,
+    previous: Option<&RuleState>,
+    current: &RuleState,
+) -> bool {
+    let Some(previous) = previous else {
+        return matches!(trigger, RuleTrigger::DeviceConnected);
+    };
+
+    match trigger {
+        RuleTrigger::DeviceConnected => false,
+        RuleTrigger::MuteStateChanged(channel, state) => {
+            let new_state = current.mute_states[*channel];
+            previous.mute_states[*channel] != new_state && state.is_none_or(|s| s == new_state)
+        }
+        RuleTrigger::ProfileLoaded(name) => {
+            previous.profile_loads != current.profile_loads
+                && name.as_ref().is_none_or(|n| n == &current.profile_name)
+        }
+        RuleTrigger::SampleStarted(bank, button) => {
+            !previous.samples_playing[*bank][*button] && current.samples_playing[*bank][*button]
+        }
+        RuleTrigger::SampleStopped(bank, button) => {
+            previous.samples_playing[*bank][*button] && !current.samples_playing[*bank][*button]
+        }
+        RuleTrigger::EffectToggled(effect, enabled) => {
+            let new_state = current.effects[*effect];
+            previous.effects[*effect] != new_state && enabled.is_none_or(|e| e == new_state)
+        }
+        RuleTrigger::VolumeAbove(channel, threshold) => {
+            previous.volumes[*channel] <= *threshold && current.volumes[*channel] > *threshold
+        }
+        RuleTrigger::VolumeBelow(channel, threshold) => {
+            previous.volumes[*channel] >= *threshold && current.volumes[*channel] < *threshold
+        }
+    }
+}
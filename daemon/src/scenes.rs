@@ -0,0 +1,51 @@
+use goxlr_types::{ChannelName, SubMixChannelName};
+use std::time::{Duration, Instant};
+
+/*
+   Crossfades volumes towards a recalled scene. The fade is driven by the device's update
+   tick, each tick works out how far through the fade we are and where every volume should
+   be at that point, so a late tick simply takes a bigger step rather than stretching the fade.
+*/
+pub struct VolumeFade<T> {
+    pub target: T,
+    from: u8,
+    to: u8,
+}
+
+impl<T> VolumeFade<T> {
+    pub fn new(target: T, from: u8, to: u8) -> Self {
+        Self { target, from, to }
+    }
+
+    pub fn volume_at(&self, progress: f64) -> u8 {
+        let from = f64::from(self.from);
+        let to = f64::from(self.to);
+        (from + (to - from) * progress).round() as u8
+    }
+}
+
+pub struct SceneFade {
+    started: Instant,
+    duration: Duration,
+    pub channels: Vec<VolumeFade<ChannelName>>,
+    pub submixes: Vec<VolumeFade<SubMixChannelName>>,
+}
+
+impl SceneFade {
+    pub fn new(duration: Duration) -> Self {
+        Self {
+            started: Instant::now(),
+            duration,
+            channels: vec![],
+            submixes: vec![],
+        }
+    }
+
+    /// How far through the fade we are, between 0 and 1
+    pub fn progress(&self) -> f64 {
+        if self.duration.is_zero() {
+            return 1.0;
+        }
+        (self.started.elapsed().as_secs_f64() / self.duration.as_secs_f64()).min(1.0)
+    }
+}
@@ -0,0 +1,287 @@
+use chrono::{DateTime, Datelike, Local, NaiveTime, TimeDelta, Weekday};
+use goxlr_ipc::{ScheduleDay, ScheduledTask};
+use std::collections::BTreeMap;
+
+// If more time than this has passed between checks, the PC was most likely suspended without
+// us being told, so anything that came due in the gap is treated as missed.
+const MISSED_GAP: TimeDelta = TimeDelta::minutes(2);
+
+/*
+   Works out which scheduled tasks have come due. Rather than tracking when each task next runs,
+   we remember when we last checked, and any task whose time falls between then and now is due,
+   this means changes to the clock or schedule don't need any special handling.
+*/
+pub struct Scheduler {
+    last_check: DateTime<Local>,
+    asleep: bool,
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self {
+            last_check: Local::now(),
+            asleep: false,
+        }
+    }
+}
+
+impl Scheduler {
+    pub fn sleep(&mut self) {
+        self.asleep = true;
+    }
+
+    /// Returns the tasks which have come due since the last check
+    pub fn check(
+        &mut self,
+        tasks: &BTreeMap<String, ScheduledTask>,
+        now: DateTime<Local>,
+    ) -> Vec<String> {
+        // Nothing runs while we're asleep, wake() will pick up anything that was missed. If we're
+        // still being checked well after going to sleep though, either the suspend failed or the
+        // wake was never reported, so carry on as if we'd woken up.
+        let missed = now - self.last_check > MISSED_GAP;
+        if self.asleep && !missed {
+            return vec![];
+        }
+        self.asleep = false;
+        self.due(tasks, now, missed)
+    }
+
+    /// Returns the tasks which were missed while asleep, and should be run now
+    pub fn wake(
+        &mut self,
+        tasks: &BTreeMap<String, ScheduledTask>,
+        now: DateTime<Local>,
+    ) -> Vec<String> {
+        self.asleep = false;
+        self.due(tasks, now, true)
+    }
+
+    fn due(
+        &mut self,
+        tasks: &BTreeMap<String, ScheduledTask>,
+        now: DateTime<Local>,
+        missed: bool,
+    ) -> Vec<String> {
+        let from = self.last_check;
+        self.last_check = now;
+
+        // If the clock has gone backwards, don't try to replay anything.
+        if now <= from {
+            return vec![];
+        }
+
+        tasks
+            .iter()
+            .filter(|(_, task)| task.enabled && (task.run_missed || !missed))
+            .filter(|(_, task)| is_due(task, from, now))
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+}
+
+// Checks whether the task's time occurs in the window after 'from', up to and including 'to'
+fn is_due(task: &ScheduledTask, from: DateTime<Local>, to: DateTime<Local>) -> bool {
+    let Some(time) = NaiveTime::from_hms_opt(task.hour.into(), task.minute.into(), 0) else {
+        return false;
+    };
+
+    let mut date = from.date_naive();
+    while date <= to.date_naive() {
+        let day = schedule_day(date.weekday());
+        if (task.days.is_empty() || task.days.contains(&day))
+            && let Some(at) = date.and_time(time).and_local_timezone(Local).earliest()
+            && at > from
+            && at <= to
+        {
+            return true;
+        }
+
+        let Some(next) = date.succ_opt() else {
+            break;
+        };
+        date = next;
+    }
+    false
+}
+
+fn schedule_day(weekday: Weekday) -> ScheduleDay {
+    match weekday {
+        Weekday::Mon => ScheduleDay::Monday,
+        Weekday::Tue => ScheduleDay::Tuesday,
+        Weekday::Wed => ScheduleDay::Wednesday,
+        Weekday::Thu => ScheduleDay::Thursday,
+        Weekday::Fri => ScheduleDay::Friday,
+        Weekday::Sat => ScheduleDay::Saturday,
+        Weekday::Sun => ScheduleDay::Sunday,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use goxlr_ipc::ScheduledAction;
+
+    // The 15th of June 2026 is a Monday
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2026, 6, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn task(hour: u8, minute: u8, days: Vec<ScheduleDay>, run_missed: bool) -> ScheduledTask {
+        ScheduledTask {
+            enabled: true,
+            serial: None,
+            hour,
+            minute,
+            days,
+            action: ScheduledAction::LoadProfile(String::from("Test")),
+            run_missed,
+        }
+    }
+
+    fn scheduler_at(last_check: DateTime<Local>) -> Scheduler {
+        Scheduler {
+            last_check,
+            asleep: false,
+        }
+    }
+
+    fn tasks() -> BTreeMap<String, ScheduledTask> {
+        let mut tasks = BTreeMap::new();
+        tasks.insert(String::from("morning"), task(9, 0, vec![], false));
+        tasks.insert(String::from("missable"), task(9, 0, vec![], true));
+        tasks.insert(String::from("evening"), task(18, 30, vec![], true));
+        tasks
+    }
+
+    #[test]
+    fn window_excludes_start_and_includes_end() {
+        let task = task(9, 0, vec![], false);
+        assert!(is_due(&task, at(15, 8, 59), at(15, 9, 0)));
+        assert!(!is_due(&task, at(15, 9, 0), at(15, 9, 1)));
+        assert!(!is_due(&task, at(15, 8, 0), at(15, 8, 59)));
+    }
+
+    #[test]
+    fn only_runs_on_selected_days() {
+        let task = task(9, 0, vec![ScheduleDay::Tuesday], false);
+        assert!(!is_due(&task, at(15, 8, 59), at(15, 9, 0)));
+        assert!(is_due(&task, at(16, 8, 59), at(16, 9, 0)));
+
+        // A window covering the whole week should still find Tuesday
+        assert!(is_due(&task, at(17, 12, 0), at(23, 12, 0)));
+    }
+
+    #[test]
+    fn window_spanning_midnight() {
+        assert!(is_due(
+            &task(0, 30, vec![], false),
+            at(15, 23, 59),
+            at(16, 0, 31)
+        ));
+        assert!(is_due(
+            &task(23, 59, vec![], false),
+            at(15, 23, 58),
+            at(16, 0, 5)
+        ));
+
+        // The day is checked against when the task's time actually falls
+        let monday = task(23, 59, vec![ScheduleDay::Monday], false);
+        let tuesday = task(23, 59, vec![ScheduleDay::Tuesday], false);
+        assert!(is_due(&monday, at(15, 23, 58), at(16, 0, 5)));
+        assert!(!is_due(&tuesday, at(15, 23, 58), at(16, 0, 5)));
+    }
+
+    #[test]
+    fn invalid_time_is_never_due() {
+        assert!(!is_due(
+            &task(24, 0, vec![], false),
+            at(15, 0, 0),
+            at(17, 0, 0)
+        ));
+        assert!(!is_due(
+            &task(9, 60, vec![], false),
+            at(15, 0, 0),
+            at(17, 0, 0)
+        ));
+    }
+
+    #[test]
+    fn due_tasks_run_once() {
+        let tasks = tasks();
+        let mut scheduler = scheduler_at(at(15, 8, 59));
+
+        assert_eq!(
+            scheduler.check(&tasks, at(15, 9, 0)),
+            ["missable", "morning"]
+        );
+        assert!(scheduler.check(&tasks, at(15, 9, 1)).is_empty());
+        assert_eq!(scheduler.last_check, at(15, 9, 1));
+    }
+
+    #[test]
+    fn disabled_tasks_are_skipped() {
+        let mut tasks = tasks();
+        tasks.get_mut("morning").unwrap().enabled = false;
+
+        let mut scheduler = scheduler_at(at(15, 8, 59));
+        assert_eq!(scheduler.check(&tasks, at(15, 9, 0)), ["missable"]);
+    }
+
+    #[test]
+    fn clock_going_backwards_replays_nothing() {
+        let tasks = tasks();
+        let mut scheduler = scheduler_at(at(15, 18, 31));
+
+        assert!(scheduler.due(&tasks, at(15, 8, 0), false).is_empty());
+        assert_eq!(scheduler.last_check, at(15, 8, 0));
+    }
+
+    #[test]
+    fn large_gap_only_runs_missable_tasks() {
+        let tasks = tasks();
+
+        // The PC was likely suspended without telling us, so this is treated as missed
+        let mut scheduler = scheduler_at(at(15, 8, 0));
+        assert_eq!(scheduler.check(&tasks, at(15, 10, 0)), ["missable"]);
+
+        // But a short gap is a normal check
+        let mut scheduler = scheduler_at(at(15, 8, 59));
+        assert_eq!(
+            scheduler.check(&tasks, at(15, 9, 1)),
+            ["missable", "morning"]
+        );
+    }
+
+    #[test]
+    fn sleeping_defers_to_wake() {
+        let tasks = tasks();
+        let mut scheduler = scheduler_at(at(15, 8, 59));
+
+        scheduler.sleep();
+        assert!(scheduler.check(&tasks, at(15, 9, 0)).is_empty());
+        assert_eq!(scheduler.last_check, at(15, 8, 59));
+
+        assert_eq!(
+            scheduler.wake(&tasks, at(15, 19, 0)),
+            ["evening", "missable"]
+        );
+        assert!(!scheduler.asleep);
+    }
+
+    #[test]
+    fn missing_wake_does_not_stop_the_schedule() {
+        let tasks = tasks();
+        let mut scheduler = scheduler_at(at(15, 8, 59));
+
+        // The wake never arrives, so once checks carry on past the gap, everything resumes
+        scheduler.sleep();
+        assert_eq!(scheduler.check(&tasks, at(15, 9, 2)), ["missable"]);
+        assert!(!scheduler.asleep);
+        assert!(scheduler.check(&tasks, at(15, 18, 29)).is_empty());
+        assert_eq!(scheduler.check(&tasks, at(15, 18, 30)), ["evening"]);
+    }
+}
@@ -35,7 +35,7 @@ use goxlr_scribbles::get_scribble_png;
 use goxlr_types::FaderName;
 
 use crate::primary_worker::{DeviceCommand, DeviceSender};
-use crate::servers::server_packet::handle_packet;
+use crate::servers::server_packet::{MicLevelSubscriptions, handle_packet};
 
 const WEB_CONTENT: Dir = include_dir!("./daemon/web-content/");
 
@@ -141,6 +141,7 @@ async fn websocket(
     // Spawn the handler (this is now where we do stuff)
     actix_web::rt::spawn(async move {
         let mut msg_stream = msg_stream.aggregate_continuations();
+        let (mut subscriptions, mut meter_rx) = MicLevelSubscriptions::new();
 
         let close_reason = loop {
             tokio::select! {
@@ -153,6 +154,15 @@ async fn websocket(
                         break e;
                     }
                 }
+                Some(meter) = meter_rx.recv() => {
+                    let message = WsResponse(WebsocketResponse {
+                        id: u64::MAX,
+                        data: DaemonResponse::MicLevelMeter(meter),
+                    });
+                    if let Err(e) = send_response(message, &mut session).await {
+                        break e;
+                    }
+                }
                 Some(Ok(msg)) = msg_stream.next() => {
                     match msg {
                         AggregatedMessage::Ping(msg) => {
@@ -168,7 +178,7 @@ async fn websocket(
                             match serde_json::from_slice::<WebsocketRequest>(msg.as_ref()) {
                                 Ok(request) => {
                                     let request_id = request.id;
-                                    let result = handle_packet(request.data, &mut usb_tx).await;
+                                    let result = handle_packet(request.data, &mut usb_tx, Some(&mut subscriptions)).await;
                                     let response = match result {
                                         Ok(resp) => {
                                             match resp {
@@ -315,7 +325,7 @@ async fn execute_command(
     let mut data = app_data.write().await;
 
     // Errors propagate weirdly in the javascript world, so send all as OK, and handle there.
-    match handle_packet(request.0, &mut data.usb_tx).await {
+    match handle_packet(request.0, &mut data.usb_tx, None).await {
         Ok(result) => HttpResponse::Ok().json(result),
         Err(error) => HttpResponse::Ok().json(DaemonResponse::Error(error.to_string())),
     }
@@ -373,7 +383,7 @@ async fn get_scribble(
     let request = DaemonRequest::GetStatus;
 
     // Pull out the scribble configuration from the Daemon Status
-    let scribble = handle_packet(request, &mut data.usb_tx)
+    let scribble = handle_packet(request, &mut data.usb_tx, None)
         .await
         .ok()
         .and_then(|response| match response {
@@ -546,7 +556,7 @@ async fn get_status(app_data: Data<RwLock<AppData>>) -> Result<DaemonStatus> {
     let mut data = app_data.write().await;
     let request = DaemonRequest::GetStatus;
 
-    let result = handle_packet(request, &mut data.usb_tx).await?;
+    let result = handle_packet(request, &mut data.usb_tx, None).await?;
     match result {
         DaemonResponse::Status(status) => Ok(status),
         _ => Err(anyhow!("Unexpected Daemon Status Result: {:?}", result)),
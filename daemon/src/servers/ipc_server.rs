@@ -6,13 +6,17 @@ use interprocess::local_socket::traits::tokio::{Listener, Stream};
 use interprocess::local_socket::{
     GenericFilePath, GenericNamespaced, ListenerOptions, ToFsName, ToNsName,
 };
+use json_patch::jsonptr::PointerBuf;
+use json_patch::{Patch, PatchOperation, ReplaceOperation};
 use log::{debug, info, warn};
 use std::fs;
 use std::path::Path;
+use tokio::sync::broadcast::Receiver as BroadcastReceiver;
+use tokio::sync::broadcast::error::RecvError;
 
-use crate::Shutdown;
 use crate::primary_worker::DeviceSender;
-use crate::servers::server_packet::handle_packet;
+use crate::servers::server_packet::{MicLevelSubscriptions, handle_packet, subscribe_events};
+use crate::{PatchEvent, Shutdown};
 
 static SOCKET_PATH: &str = "/tmp/goxlr.socket";
 static NAMED_PIPE: &str = "@goxlr.socket";
@@ -110,26 +114,75 @@ async fn handle_connection(
     mut socket: Socket<DaemonRequest, DaemonResponse>,
     mut usb_tx: DeviceSender,
 ) {
-    while let Some(msg) = socket.read().await {
-        match msg {
-            Ok(msg) => match handle_packet(msg, &mut usb_tx).await {
-                Ok(response) => {
-                    if let Err(e) = socket.send(response).await {
-                        warn!("Couldn't reply to {:?}: {}", socket.address(), e);
-                        return;
+    let (mut subscriptions, mut meter_rx) = MicLevelSubscriptions::new();
+    let mut events: Option<BroadcastReceiver<PatchEvent>> = None;
+
+    loop {
+        tokio::select! {
+            msg = socket.read() => {
+                let Some(msg) = msg else {
+                    break;
+                };
+
+                let response = match msg {
+                    Ok(DaemonRequest::SubscribeEvents) => match subscribe_events(&usb_tx).await {
+                        Ok((status, receiver)) => {
+                            events = Some(receiver);
+                            DaemonResponse::Status(status)
+                        }
+                        Err(e) => DaemonResponse::Error(e.to_string()),
+                    },
+                    Ok(DaemonRequest::UnsubscribeEvents) => {
+                        events = None;
+                        DaemonResponse::Ok
                     }
-                }
-                Err(e) => {
-                    if let Err(e) = socket.send(DaemonResponse::Error(e.to_string())).await {
-                        warn!("Couldn't reply to {:?}: {}", socket.address(), e);
-                        return;
+                    Ok(msg) => match handle_packet(msg, &mut usb_tx, Some(&mut subscriptions)).await {
+                        Ok(response) => response,
+                        Err(e) => DaemonResponse::Error(e.to_string()),
+                    },
+                    Err(e) => {
+                        warn!("Invalid message from {:?}: {}", socket.address(), e);
+                        DaemonResponse::Error(e.to_string())
                     }
+                };
+
+                if let Err(e) = socket.send(response).await {
+                    warn!("Couldn't reply to {:?}: {}", socket.address(), e);
+                    return;
                 }
-            },
-            Err(e) => {
-                warn!("Invalid message from {:?}: {}", socket.address(), e);
-                if let Err(e) = socket.send(DaemonResponse::Error(e.to_string())).await {
-                    warn!("Could not reply to {:?}: {}", socket.address(), e);
+            }
+            Some(meter) = meter_rx.recv() => {
+                if let Err(e) = socket.send(DaemonResponse::MicLevelMeter(meter)).await {
+                    warn!("Couldn't send Mic Level to {:?}: {}", socket.address(), e);
+                    return;
+                }
+            }
+            event = next_event(&mut events) => {
+                let patch = match event {
+                    Ok(event) => event.data,
+                    Err(RecvError::Lagged(count)) => {
+                        // We've missed some patches, so the client's status can't be trusted
+                        // anymore, resubscribe and replace the whole thing.
+                        warn!("{:?} missed {} events, resyncing", socket.address(), count);
+                        match resync_events(&usb_tx).await {
+                            Ok((patch, receiver)) => {
+                                events = Some(receiver);
+                                patch
+                            }
+                            Err(e) => {
+                                warn!("Unable to resync {:?}: {}", socket.address(), e);
+                                return;
+                            }
+                        }
+                    }
+                    Err(RecvError::Closed) => {
+                        events = None;
+                        continue;
+                    }
+                };
+
+                if let Err(e) = socket.send(DaemonResponse::Patch(patch)).await {
+                    warn!("Couldn't send Patch to {:?}: {}", socket.address(), e);
                     return;
                 }
             }
@@ -137,3 +190,21 @@ async fn handle_connection(
     }
     debug!("Disconnected {:?}", socket.address());
 }
+
+async fn next_event(
+    events: &mut Option<BroadcastReceiver<PatchEvent>>,
+) -> Result<PatchEvent, RecvError> {
+    match events {
+        Some(receiver) => receiver.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+async fn resync_events(usb_tx: &DeviceSender) -> Result<(Patch, BroadcastReceiver<PatchEvent>)> {
+    let (status, receiver) = subscribe_events(usb_tx).await?;
+    let patch = Patch(vec![PatchOperation::Replace(ReplaceOperation {
+        path: PointerBuf::root(),
+        value: serde_json::to_value(status)?,
+    })]);
+    Ok((patch, receiver))
+}
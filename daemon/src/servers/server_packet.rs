@@ -1,11 +1,98 @@
+use crate::PatchEvent;
 use crate::primary_worker::{DeviceCommand, DeviceSender};
-use anyhow::{Context, Result, anyhow};
-use goxlr_ipc::{DaemonRequest, DaemonResponse};
-use tokio::sync::oneshot;
+use anyhow::{Context, Result, anyhow, bail};
+use goxlr_ipc::{DaemonRequest, DaemonResponse, DaemonStatus, MicLevelMeter};
+use std::collections::HashMap;
+use tokio::sync::broadcast::Receiver as BroadcastReceiver;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+
+// Tracks the Mic Level subscriptions of a single persistent connection, the meters from each
+// subscribed device are forwarded into one channel that the connection sends from.
+pub struct MicLevelSubscriptions {
+    sender: mpsc::Sender<MicLevelMeter>,
+    tasks: HashMap<String, JoinHandle<()>>,
+}
+
+impl MicLevelSubscriptions {
+    pub fn new() -> (Self, mpsc::Receiver<MicLevelMeter>) {
+        let (sender, receiver) = mpsc::channel(32);
+        let subscriptions = Self {
+            sender,
+            tasks: HashMap::new(),
+        };
+        (subscriptions, receiver)
+    }
+
+    async fn subscribe(&mut self, serial: String, rate: u8, usb_tx: &DeviceSender) -> Result<()> {
+        let (tx, rx) = oneshot::channel();
+        usb_tx
+            .send(DeviceCommand::SubscribeMicLevel(serial.clone(), rate, tx))
+            .await
+            .map_err(|e| anyhow!(e.to_string()))
+            .context("Could not communicate with the GoXLR device")?;
+        let mut subscription = rx
+            .await
+            .context("Could not execute the command on the GoXLR device")??;
+
+        // The subscription lives in the task, so aborting the task is what unsubscribes.
+        let sender = self.sender.clone();
+        let task = tokio::spawn(async move {
+            loop {
+                match subscription.receiver.recv().await {
+                    Ok(meter) => {
+                        if sender.send(meter).await.is_err() {
+                            break;
+                        }
+                    }
+                    // Slow connections will simply miss some samples..
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                }
+            }
+        });
+
+        if let Some(previous) = self.tasks.insert(serial, task) {
+            previous.abort();
+        }
+        Ok(())
+    }
+
+    fn unsubscribe(&mut self, serial: &str) {
+        if let Some(task) = self.tasks.remove(serial) {
+            task.abort();
+        }
+    }
+}
+
+impl Drop for MicLevelSubscriptions {
+    fn drop(&mut self) {
+        // Dropping the subscriptions is what tells the primary worker to stop sampling.
+        for task in self.tasks.values() {
+            task.abort();
+        }
+    }
+}
+
+// Fetches the current status along with a receiver for every patch made after it.
+pub async fn subscribe_events(
+    usb_tx: &DeviceSender,
+) -> Result<(DaemonStatus, BroadcastReceiver<PatchEvent>)> {
+    let (tx, rx) = oneshot::channel();
+    usb_tx
+        .send(DeviceCommand::SubscribeEvents(tx))
+        .await
+        .map_err(|e| anyhow!(e.to_string()))
+        .context("Could not communicate with the device task")?;
+    rx.await
+        .context("Could not execute the command on the device task")
+}
 
 pub async fn handle_packet(
     request: DaemonRequest,
     usb_tx: &mut DeviceSender,
+    subscriptions: Option<&mut MicLevelSubscriptions>,
 ) -> Result<DaemonResponse> {
     match request {
         DaemonRequest::Ping => Ok(DaemonResponse::Ok),
@@ -49,6 +136,27 @@ pub async fn handle_packet(
             }
         }
 
+        DaemonRequest::SubscribeMicLevel(serial, rate) => {
+            let Some(subscriptions) = subscriptions else {
+                bail!("Mic Level Subscriptions require a persistent connection");
+            };
+            subscriptions.subscribe(serial, rate, usb_tx).await?;
+            Ok(DaemonResponse::Ok)
+        }
+
+        DaemonRequest::UnsubscribeMicLevel(serial) => {
+            let Some(subscriptions) = subscriptions else {
+                bail!("Mic Level Subscriptions require a persistent connection");
+            };
+            subscriptions.unsubscribe(&serial);
+            Ok(DaemonResponse::Ok)
+        }
+
+        // These change the state of the connection itself, so are handled by the IPC server.
+        DaemonRequest::SubscribeEvents | DaemonRequest::UnsubscribeEvents => {
+            bail!("Event Subscriptions are only available on the IPC Socket");
+        }
+
         DaemonRequest::Command(serial, command) => {
             let (tx, rx) = oneshot::channel();
             usb_tx
@@ -61,6 +169,18 @@ pub async fn handle_packet(
             Ok(DaemonResponse::Ok)
         }
 
+        DaemonRequest::Batch(serial, commands) => {
+            let (tx, rx) = oneshot::channel();
+            usb_tx
+                .send(DeviceCommand::RunDeviceBatch(serial, commands, tx))
+                .await
+                .map_err(|e| anyhow!(e.to_string()))
+                .context("Could not communicate with the GoXLR device")?;
+            rx.await
+                .context("Could not execute the command on the GoXLR device")??;
+            Ok(DaemonResponse::Ok)
+        }
+
         DaemonRequest::RunFirmwareUpdate(serial, path, force) => {
             let (tx, rx) = oneshot::channel();
             usb_tx
@@ -2,12 +2,15 @@ use crate::mic_profile::DEFAULT_MIC_PROFILE_NAME;
 use crate::profile::DEFAULT_PROFILE_NAME;
 use anyhow::{Context, Result};
 use directories::ProjectDirs;
-use goxlr_ipc::{FirmwareSource, GoXLRCommand, LogLevel};
-use goxlr_types::VodMode;
+use goxlr_ipc::{
+    AppRule, ButtonBinding, Ducking, FirmwareSource, GoXLRCommand, LinkGroup, LogLevel, PushToTalk,
+    Rule, Scene, ScheduledTask, VoiceActivity,
+};
 use goxlr_types::VodMode::Routable;
+use goxlr_types::{Button, VodMode};
 use log::{debug, error, info, warn};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
 use std::fs::{File, create_dir_all};
 use std::io::ErrorKind;
@@ -75,6 +78,8 @@ impl SettingsHandle {
                 firmware_source: None,
                 devices: Some(Default::default()),
                 sample_gain: Some(Default::default()),
+                schedule: Some(Default::default()),
+                link_groups: Some(Default::default()),
             }
         });
 
@@ -154,6 +159,14 @@ impl SettingsHandle {
             settings.devices = Some(Default::default());
         }
 
+        if settings.schedule.is_none() {
+            settings.schedule = Some(Default::default());
+        }
+
+        if settings.link_groups.is_none() {
+            settings.link_groups = Some(Default::default());
+        }
+
         let handle = SettingsHandle {
             path,
             data_dir: data_dir.to_path_buf(),
@@ -247,6 +260,26 @@ impl SettingsHandle {
         settings.macos_handle_aggregates.unwrap()
     }
 
+    pub async fn set_schedule(&self, schedule: BTreeMap<String, ScheduledTask>) {
+        let mut settings = self.settings.write().await;
+        settings.schedule = Some(schedule);
+    }
+
+    pub async fn get_schedule(&self) -> BTreeMap<String, ScheduledTask> {
+        let settings = self.settings.read().await;
+        settings.schedule.clone().unwrap()
+    }
+
+    pub async fn set_link_groups(&self, groups: BTreeMap<String, LinkGroup>) {
+        let mut settings = self.settings.write().await;
+        settings.link_groups = Some(groups);
+    }
+
+    pub async fn get_link_groups(&self) -> BTreeMap<String, LinkGroup> {
+        let settings = self.settings.read().await;
+        settings.link_groups.clone().unwrap()
+    }
+
     pub async fn get_profile_directory(&self) -> PathBuf {
         let settings = self.settings.read().await;
         if let Some(directory) = settings.profile_directory.clone() {
@@ -375,6 +408,51 @@ impl SettingsHandle {
         vec![]
     }
 
+    pub async fn get_device_rules(&self, device_serial: &str) -> BTreeMap<String, Rule> {
+        let settings = self.settings.read().await;
+        let value = settings
+            .devices
+            .as_ref()
+            .unwrap()
+            .get(device_serial)
+            .map(|d| d.rules.clone());
+
+        if let Some(value) = value {
+            return value;
+        }
+        BTreeMap::new()
+    }
+
+    pub async fn get_device_app_rules(&self, device_serial: &str) -> BTreeMap<String, AppRule> {
+        let settings = self.settings.read().await;
+        let value = settings
+            .devices
+            .as_ref()
+            .unwrap()
+            .get(device_serial)
+            .map(|d| d.app_rules.clone());
+
+        if let Some(value) = value {
+            return value;
+        }
+        BTreeMap::new()
+    }
+
+    pub async fn get_device_scenes(&self, device_serial: &str) -> BTreeMap<String, Scene> {
+        let settings = self.settings.read().await;
+        let value = settings
+            .devices
+            .as_ref()
+            .unwrap()
+            .get(device_serial)
+            .map(|d| d.scenes.clone());
+
+        if let Some(value) = value {
+            return value;
+        }
+        BTreeMap::new()
+    }
+
     pub async fn get_device_sleep_commands(&self, device_serial: &str) -> Vec<GoXLRCommand> {
         let settings = self.settings.read().await;
         let value = settings
@@ -390,6 +468,24 @@ impl SettingsHandle {
         vec![]
     }
 
+    pub async fn get_device_button_bindings(
+        &self,
+        device_serial: &str,
+    ) -> BTreeMap<Button, ButtonBinding> {
+        let settings = self.settings.read().await;
+        let value = settings
+            .devices
+            .as_ref()
+            .unwrap()
+            .get(device_serial)
+            .map(|d| d.button_bindings.clone());
+
+        if let Some(value) = value {
+            return value;
+        }
+        BTreeMap::new()
+    }
+
     pub async fn get_device_wake_commands(&self, device_serial: &str) -> Vec<GoXLRCommand> {
         let settings = self.settings.read().await;
         let value = settings
@@ -405,6 +501,36 @@ impl SettingsHandle {
         vec![]
     }
 
+    pub async fn get_device_lock_commands(&self, device_serial: &str) -> Vec<GoXLRCommand> {
+        let settings = self.settings.read().await;
+        let value = settings
+            .devices
+            .as_ref()
+            .unwrap()
+            .get(device_serial)
+            .map(|d| d.lock_commands.clone());
+
+        if let Some(value) = value {
+            return value;
+        }
+        vec![]
+    }
+
+    pub async fn get_device_unlock_commands(&self, device_serial: &str) -> Vec<GoXLRCommand> {
+        let settings = self.settings.read().await;
+        let value = settings
+            .devices
+            .as_ref()
+            .unwrap()
+            .get(device_serial)
+            .map(|d| d.unlock_commands.clone());
+
+        if let Some(value) = value {
+            return value;
+        }
+        vec![]
+    }
+
     pub async fn get_device_sampler_pre_buffer(&self, device_serial: &str) -> u16 {
         let settings = self.settings.read().await;
         let value = settings
@@ -450,6 +576,21 @@ impl SettingsHandle {
         true
     }
 
+    pub async fn get_device_panic_mutes_chat(&self, device_serial: &str) -> bool {
+        let settings = self.settings.read().await;
+        let value = settings
+            .devices
+            .as_ref()
+            .unwrap()
+            .get(device_serial)
+            .map(|d| d.panic_mutes_chat.unwrap_or(false));
+
+        if let Some(value) = value {
+            return value;
+        }
+        false
+    }
+
     pub async fn get_device_lock_faders(&self, device_serial: &str) -> bool {
         let settings = self.settings.read().await;
         let value = settings
@@ -479,6 +620,51 @@ impl SettingsHandle {
         false
     }
 
+    pub async fn get_device_voice_activity(&self, device_serial: &str) -> VoiceActivity {
+        let settings = self.settings.read().await;
+        let value = settings
+            .devices
+            .as_ref()
+            .unwrap()
+            .get(device_serial)
+            .map(|d| d.voice_activity.unwrap_or_default());
+
+        if let Some(value) = value {
+            return value;
+        }
+        VoiceActivity::default()
+    }
+
+    pub async fn get_device_ducking(&self, device_serial: &str) -> Ducking {
+        let settings = self.settings.read().await;
+        let value = settings
+            .devices
+            .as_ref()
+            .unwrap()
+            .get(device_serial)
+            .map(|d| d.ducking.clone().unwrap_or_default());
+
+        if let Some(value) = value {
+            return value;
+        }
+        Ducking::default()
+    }
+
+    pub async fn get_device_push_to_talk(&self, device_serial: &str) -> PushToTalk {
+        let settings = self.settings.read().await;
+        let value = settings
+            .devices
+            .as_ref()
+            .unwrap()
+            .get(device_serial)
+            .map(|d| d.push_to_talk.unwrap_or_default());
+
+        if let Some(value) = value {
+            return value;
+        }
+        PushToTalk::default()
+    }
+
     pub async fn get_device_vod_mode(&self, device_serial: &str) -> VodMode {
         let settings = self.settings.read().await;
         let value = settings
@@ -559,6 +745,43 @@ impl SettingsHandle {
         mic_profile_name.clone_into(&mut entry.mic_profile);
     }
 
+    pub async fn set_device_rules(&self, device_serial: &str, rules: BTreeMap<String, Rule>) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.rules = rules;
+    }
+
+    pub async fn set_device_app_rules(
+        &self,
+        device_serial: &str,
+        rules: BTreeMap<String, AppRule>,
+    ) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.app_rules = rules;
+    }
+
+    pub async fn set_device_scenes(&self, device_serial: &str, scenes: BTreeMap<String, Scene>) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.scenes = scenes;
+    }
+
     pub async fn set_device_shutdown_commands(
         &self,
         device_serial: &str,
@@ -589,6 +812,21 @@ impl SettingsHandle {
         commands.clone_into(&mut entry.sleep_commands);
     }
 
+    pub async fn set_device_button_bindings(
+        &self,
+        device_serial: &str,
+        bindings: BTreeMap<Button, ButtonBinding>,
+    ) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.button_bindings = bindings;
+    }
+
     pub async fn set_device_wake_commands(&self, device_serial: &str, commands: Vec<GoXLRCommand>) {
         let mut settings = self.settings.write().await;
         let entry = settings
@@ -600,6 +838,32 @@ impl SettingsHandle {
         commands.clone_into(&mut entry.wake_commands);
     }
 
+    pub async fn set_device_lock_commands(&self, device_serial: &str, commands: Vec<GoXLRCommand>) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        commands.clone_into(&mut entry.lock_commands);
+    }
+
+    pub async fn set_device_unlock_commands(
+        &self,
+        device_serial: &str,
+        commands: Vec<GoXLRCommand>,
+    ) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        commands.clone_into(&mut entry.unlock_commands);
+    }
+
     pub async fn set_device_sampler_pre_buffer(&self, device_serial: &str, duration: u16) {
         let mut settings = self.settings.write().await;
         let entry = settings
@@ -633,6 +897,17 @@ impl SettingsHandle {
         entry.chat_mute_mutes_mic_to_chat = Some(setting);
     }
 
+    pub async fn set_device_panic_mutes_chat(&self, device_serial: &str, setting: bool) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.panic_mutes_chat = Some(setting);
+    }
+
     pub async fn set_device_lock_faders(&self, device_serial: &str, setting: bool) {
         let mut settings = self.settings.write().await;
         let entry = settings
@@ -655,6 +930,39 @@ impl SettingsHandle {
         entry.enable_monitor_with_fx = Some(setting);
     }
 
+    pub async fn set_device_voice_activity(&self, device_serial: &str, setting: VoiceActivity) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.voice_activity = Some(setting);
+    }
+
+    pub async fn set_device_ducking(&self, device_serial: &str, setting: Ducking) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.ducking = Some(setting);
+    }
+
+    pub async fn set_device_push_to_talk(&self, device_serial: &str, setting: PushToTalk) {
+        let mut settings = self.settings.write().await;
+        let entry = settings
+            .devices
+            .as_mut()
+            .unwrap()
+            .entry(device_serial.to_owned())
+            .or_insert_with(DeviceSettings::default);
+        entry.push_to_talk = Some(setting);
+    }
+
     pub async fn set_device_vod_mode(&self, device_serial: &str, setting: VodMode) {
         let mut settings = self.settings.write().await;
         let entry = settings
@@ -720,6 +1028,8 @@ pub struct Settings {
     firmware_source: Option<FirmwareSource>,
     devices: Option<HashMap<String, DeviceSettings>>,
     sample_gain: Option<HashMap<String, u8>>,
+    schedule: Option<BTreeMap<String, ScheduledTask>>,
+    link_groups: Option<BTreeMap<String, LinkGroup>>,
 }
 
 impl Settings {
@@ -804,6 +1114,9 @@ struct DeviceSettings {
     // 'Voice Chat Mute All Also Mutes Mic to Chat Mic' O_O
     chat_mute_mutes_mic_to_chat: Option<bool>,
 
+    // Whether the Panic button also pulls everything from the Chat Mic
+    panic_mutes_chat: Option<bool>,
+
     // Disables the Movement of the Faders when Muting to All (full device only)
     lock_faders: Option<bool>,
 
@@ -819,10 +1132,33 @@ struct DeviceSettings {
     // VoD 'Mode'
     vod_mode: Option<VodMode>,
 
+    // Voice Activity Detection
+    voice_activity: Option<VoiceActivity>,
+
+    // Ducking of other channels
+    ducking: Option<Ducking>,
+
+    // Push to Talk on the Cough Button
+    push_to_talk: Option<PushToTalk>,
+
+    // Saved Mixer States
+    scenes: BTreeMap<String, Scene>,
+
+    // Actions to run on changes in the device state
+    rules: BTreeMap<String, Rule>,
+
+    // Configuration changes while an application is running
+    app_rules: BTreeMap<String, AppRule>,
+
     // 'Shutdown' commands..
     shutdown_commands: Vec<GoXLRCommand>,
     sleep_commands: Vec<GoXLRCommand>,
     wake_commands: Vec<GoXLRCommand>,
+    lock_commands: Vec<GoXLRCommand>,
+    unlock_commands: Vec<GoXLRCommand>,
+
+    // Hold and Double Press Actions, only buttons with something bound are stored
+    button_bindings: BTreeMap<Button, ButtonBinding>,
 }
 
 impl Default for DeviceSettings {
@@ -834,16 +1170,28 @@ impl Default for DeviceSettings {
             hold_delay: Some(500),
             sampler_pre_buffer: None,
             chat_mute_mutes_mic_to_chat: Some(true),
+            panic_mutes_chat: Some(false),
             lock_faders: Some(false),
             enable_monitor_with_fx: Some(false),
             sampler_reset_on_clear: Some(true),
             sampler_fade_duration: Some(500),
 
             vod_mode: Some(Routable),
+            voice_activity: Some(VoiceActivity::default()),
+            ducking: Some(Ducking::default()),
+            push_to_talk: Some(PushToTalk::default()),
+
+            scenes: BTreeMap::new(),
+            rules: BTreeMap::new(),
+            app_rules: BTreeMap::new(),
 
             shutdown_commands: vec![],
             sleep_commands: vec![],
             wake_commands: vec![],
+            lock_commands: vec![],
+            unlock_commands: vec![],
+
+            button_bindings: BTreeMap::new(),
         }
     }
 }
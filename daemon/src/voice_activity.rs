@@ -0,0 +1,78 @@
+use goxlr_ipc::VoiceActivity;
+use std::time::{Duration, Instant};
+
+/*
+   A simple level based Voice Activity Detector, the GoXLR already applies the gate and
+   compressor before the level is read, so this is mostly about smoothing out the edges. The
+   level has to stay above the threshold for the attack time before we consider the user to be
+   speaking, and then below it for the hold time before we consider them to have stopped.
+*/
+pub struct VoiceActivityDetector {
+    config: VoiceActivity,
+    speaking: bool,
+    above_since: Option<Instant>,
+    last_above: Option<Instant>,
+}
+
+impl VoiceActivityDetector {
+    pub fn new(config: VoiceActivity) -> Self {
+        Self {
+            config,
+            speaking: false,
+            above_since: None,
+            last_above: None,
+        }
+    }
+
+    pub fn config(&self) -> VoiceActivity {
+        self.config
+    }
+
+    pub fn set_config(&mut self, config: VoiceActivity) {
+        if !config.enabled {
+            self.reset();
+        }
+        self.config = config;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
+
+    pub fn is_speaking(&self) -> bool {
+        self.speaking
+    }
+
+    /// Processes a new mic level (in dB), returns true if the speaking state changed
+    pub fn process(&mut self, level: f64) -> bool {
+        let now = Instant::now();
+        let previous = self.speaking;
+
+        if level >= self.config.threshold.into() {
+            let above_since = *self.above_since.get_or_insert(now);
+            self.last_above = Some(now);
+
+            let attack = Duration::from_millis(self.config.attack.into());
+            if now.duration_since(above_since) >= attack {
+                self.speaking = true;
+            }
+        } else {
+            self.above_since = None;
+
+            let hold = Duration::from_millis(self.config.hold.into());
+            if let Some(last_above) = self.last_above
+                && now.duration_since(last_above) >= hold
+            {
+                self.speaking = false;
+            }
+        }
+
+        previous != self.speaking
+    }
+
+    fn reset(&mut self) {
+        self.speaking = false;
+        self.above_since = None;
+        self.last_above = None;
+    }
+}
@@ -3,12 +3,18 @@ use crate::clients::ipc::ipc_socket::Socket;
 use crate::{DaemonRequest, DaemonResponse, DaemonStatus, GoXLRCommand, HttpSettings};
 use anyhow::{Context, Result, anyhow, bail};
 use async_trait::async_trait;
+use json_patch::Patch;
+use std::collections::VecDeque;
 
 #[derive(Debug)]
 pub struct IPCClient {
     socket: Socket<DaemonResponse, DaemonRequest>,
     status: DaemonStatus,
     http_settings: HttpSettings,
+
+    // Events pushed by the daemon while we were waiting on a response, any Patches in here have
+    // already been applied to the status.
+    events: VecDeque<DaemonResponse>,
 }
 
 impl IPCClient {
@@ -17,8 +23,56 @@ impl IPCClient {
             socket,
             status: DaemonStatus::default(),
             http_settings: Default::default(),
+            events: VecDeque::new(),
         }
     }
+
+    /// Turns this connection into an event stream, the status is refreshed and then kept up to
+    /// date by the Patches returned from `next_event`.
+    pub async fn subscribe(&mut self) -> Result<()> {
+        self.send(DaemonRequest::SubscribeEvents).await
+    }
+
+    pub async fn unsubscribe(&mut self) -> Result<()> {
+        self.send(DaemonRequest::UnsubscribeEvents).await?;
+
+        // Anything queued is from the old subscription..
+        self.events.clear();
+        Ok(())
+    }
+
+    /// Waits for the next Patch or Mic Level Meter pushed by the daemon. Patches have already
+    /// been applied to the status when they're returned.
+    pub async fn next_event(&mut self) -> Result<DaemonResponse> {
+        if let Some(event) = self.events.pop_front() {
+            return Ok(event);
+        }
+
+        let message = self.read().await?;
+        match &message {
+            DaemonResponse::Patch(patch) => self.apply_patch(patch)?,
+            DaemonResponse::MicLevelMeter(_) => {}
+            _ => bail!("Received a response while waiting for an event, shouldn't happen!"),
+        }
+        Ok(message)
+    }
+
+    async fn read(&mut self) -> Result<DaemonResponse> {
+        self.socket
+            .read()
+            .await
+            .context("Failed to retrieve the command result from the GoXLR daemon process")?
+            .context("Failed to parse the command result from the GoXLR daemon process")
+    }
+
+    fn apply_patch(&mut self, patch: &Patch) -> Result<()> {
+        let mut status = serde_json::to_value(&self.status)?;
+        json_patch::patch(&mut status, patch).context("Unable to apply Patch to the status")?;
+
+        self.status = serde_json::from_value(status)?;
+        self.http_settings = self.status.config.http_settings.clone();
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -28,12 +82,17 @@ impl Client for IPCClient {
             .send(request)
             .await
             .context("Failed to send a command to the GoXLR daemon process")?;
-        let result = self
-            .socket
-            .read()
-            .await
-            .context("Failed to retrieve the command result from the GoXLR daemon process")?
-            .context("Failed to parse the command result from the GoXLR daemon process")?;
+
+        // If we're subscribed, events may arrive before the response, so hold on to them..
+        let result = loop {
+            let message = self.read().await?;
+            match &message {
+                DaemonResponse::Patch(patch) => self.apply_patch(patch)?,
+                DaemonResponse::MicLevelMeter(_) => {}
+                _ => break message,
+            }
+            self.events.push_back(message);
+        };
 
         match result {
             DaemonResponse::Status(status) => {
@@ -46,8 +105,8 @@ impl Client for IPCClient {
             DaemonResponse::MicLevel(_level) => {
                 bail!("Received Mic Level as Response, shouldn't happen!");
             }
-            DaemonResponse::Patch(_patch) => {
-                Err(anyhow!("Received Patch as response, shouldn't happen!"))
+            DaemonResponse::MicLevelMeter(_) | DaemonResponse::Patch(_) => {
+                bail!("Received an Event as a Response, shouldn't happen!");
             }
         }
     }
@@ -43,6 +43,9 @@ impl Client for WebClient {
             DaemonResponse::MicLevel(_level) => {
                 bail!("Received Mic Level as response, shouldn't happen!")
             }
+            DaemonResponse::MicLevelMeter(_meter) => {
+                bail!("Received Mic Level Meter as response, shouldn't happen!")
+            }
             DaemonResponse::Patch(_patch) => {
                 bail!("Received Patch as response, shouldn't happen!")
             }
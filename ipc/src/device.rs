@@ -1,15 +1,15 @@
-use crate::{ColourWay, FirmwareSource, GoXLRCommand, LogLevel, UpdateState};
-use enum_map::EnumMap;
+use crate::{ColourWay, DaemonCommand, FirmwareSource, GoXLRCommand, LogLevel, UpdateState};
+use enum_map::{Enum, EnumMap};
 use goxlr_types::MuteState::Unmuted;
 use goxlr_types::{
     AnimationMode, Button, ButtonColourOffStyle, ChannelName, CompressorAttackTime,
-    CompressorRatio, CompressorReleaseTime, DeviceType, DisplayMode, DriverInterface, EchoStyle,
-    EffectBankPresets, EncoderColourTargets, EqFrequencies, FaderDisplayStyle, FaderName,
-    FirmwareDetails, FirmwareVersions, GateTimes, GenderStyle, HardTuneSource, HardTuneStyle,
-    InputDevice, MegaphoneStyle, MicrophoneType, MiniEqFrequencies, Mix, MuteFunction, MuteState,
-    OutputDevice, PitchStyle, ReverbStyle, RobotStyle, SampleBank, SampleButtons, SamplePlayOrder,
-    SamplePlaybackMode, SamplerColourTargets, SimpleColourTargets, SubMixChannelName,
-    VersionNumber, VodMode, WaterfallDirection,
+    CompressorRatio, CompressorReleaseTime, DeviceType, DisplayMode, DriverInterface,
+    DuckingSource, EchoStyle, EffectBankPresets, EncoderColourTargets, EqFrequencies,
+    FaderDisplayStyle, FaderName, FirmwareDetails, FirmwareVersions, GateTimes, GenderStyle,
+    HardTuneSource, HardTuneStyle, InputDevice, MegaphoneStyle, MicrophoneType, MiniEqFrequencies,
+    Mix, MuteFunction, MuteState, OutputDevice, PitchStyle, ReverbStyle, RobotStyle, SampleBank,
+    SampleButtons, SamplePlayOrder, SamplePlaybackMode, SamplerColourTargets, SimpleColourTargets,
+    SubMixChannelName, VersionNumber, VodMode, WaterfallDirection,
 };
 use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashMap};
@@ -22,6 +22,52 @@ pub struct DaemonStatus {
     pub mixers: HashMap<String, MixerStatus>,
     pub paths: Paths,
     pub files: Files,
+    pub schedule: BTreeMap<String, ScheduledTask>,
+    pub link_groups: BTreeMap<String, LinkGroup>,
+}
+
+// Devices (by serial) which mirror each other, a change made to any member is replayed on the
+// rest for each of the enabled categories.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkGroup {
+    pub serials: Vec<String>,
+    pub volumes: bool,
+    pub mutes: bool,
+    pub lighting: bool,
+    pub effects: bool,
+}
+
+// Something to run at a set time of day, against a single device (by serial) or against every
+// device if no serial is given. If no days are set, the task runs every day.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledTask {
+    pub enabled: bool,
+    pub serial: Option<String>,
+    pub hour: u8,
+    pub minute: u8,
+    pub days: Vec<ScheduleDay>,
+    pub action: ScheduledAction,
+
+    // Whether the task should still run on wake if its time passed while the PC was asleep
+    pub run_missed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ScheduledAction {
+    Commands(Vec<GoXLRCommand>),
+    LoadProfile(String),
+    LoadMicProfile(String),
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ScheduleDay {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -75,6 +121,9 @@ pub struct MixerStatus {
     pub shutdown_commands: Vec<GoXLRCommand>,
     pub sleep_commands: Vec<GoXLRCommand>,
     pub wake_commands: Vec<GoXLRCommand>,
+    pub lock_commands: Vec<GoXLRCommand>,
+    pub unlock_commands: Vec<GoXLRCommand>,
+    pub button_bindings: BTreeMap<Button, ButtonBinding>,
     pub fader_status: EnumMap<FaderName, FaderStatus>,
     pub mic_status: MicSettings,
     pub levels: Levels,
@@ -87,6 +136,17 @@ pub struct MixerStatus {
     pub button_down: EnumMap<Button, bool>,
     pub profile_name: String,
     pub mic_profile_name: String,
+    pub speaking: bool,
+    pub ducked: bool,
+    pub panic: bool,
+    pub history: History,
+}
+
+// Descriptions of the changes which can be undone and redone, most recent first.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct History {
+    pub undo: Vec<String>,
+    pub redo: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -124,15 +184,21 @@ pub struct HardwareStatus {
 pub struct FaderStatus {
     pub channel: ChannelName,
     pub mute_type: MuteFunction,
+    pub mute_targets: Option<Vec<OutputDevice>>,
     pub scribble: Option<Scribble>,
     pub mute_state: MuteState,
+
+    // Seconds until a timed mute lifts itself
+    pub mute_timer: Option<u32>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Copy)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CoughButton {
     pub is_toggle: bool,
     pub mute_type: MuteFunction,
+    pub mute_targets: Option<Vec<OutputDevice>>,
     pub state: MuteState,
+    pub mute_timer: Option<u32>,
 }
 
 impl Default for FaderStatus {
@@ -140,8 +206,10 @@ impl Default for FaderStatus {
         FaderStatus {
             channel: ChannelName::Mic,
             mute_type: MuteFunction::All,
+            mute_targets: None,
             scribble: None,
             mute_state: Unmuted,
+            mute_timer: None,
         }
     }
 }
@@ -162,6 +230,7 @@ pub struct Levels {
     pub submix_supported: bool,
     pub output_monitor: OutputDevice,
     pub volumes: EnumMap<ChannelName, u8>,
+    pub mutes: EnumMap<ChannelName, Option<MuteFunction>>,
     pub submix: Option<Submixes>,
     pub bleep: i8,
     pub deess: u8,
@@ -404,11 +473,176 @@ pub struct Settings {
     pub display: Display,
     pub mute_hold_duration: u16,
     pub vc_mute_also_mute_cm: bool,
+    pub panic_mutes_chat: bool,
     pub enable_monitor_with_fx: bool,
     pub reset_sampler_on_clear: bool,
     pub lock_faders: bool,
     pub fade_duration: u32,
     pub vod_mode: VodMode,
+    pub voice_activity: VoiceActivity,
+    pub ducking: Ducking,
+    pub push_to_talk: PushToTalk,
+    pub scenes: BTreeMap<String, Scene>,
+    pub rules: BTreeMap<String, Rule>,
+    pub app_rules: BTreeMap<String, AppRule>,
+}
+
+// Switches profile, mic profile and / or scene while an application is running, putting the
+// previous ones back once it closes. The process is matched against the executable path if it
+// contains a path separator, otherwise against the process name.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppRule {
+    pub enabled: bool,
+    pub process: String,
+    pub profile: Option<String>,
+    pub mic_profile: Option<String>,
+    pub scene: Option<String>,
+}
+
+// Rules run their commands when the trigger is seen in a change of the device's state. Changes
+// made by a rule's own commands are not checked against the rules, so rules can't chain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Rule {
+    pub enabled: bool,
+    pub trigger: RuleTrigger,
+    pub commands: Vec<GoXLRCommand>,
+    pub daemon_commands: Vec<DaemonCommand>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum RuleTrigger {
+    // The channel's mute state changed, to a specific state if one is given
+    MuteStateChanged(ChannelName, Option<MuteState>),
+
+    // A profile was loaded (including reloading the current one), optionally only a specific one
+    ProfileLoaded(Option<String>),
+
+    SampleStarted(SampleBank, SampleButtons),
+    SampleStopped(SampleBank, SampleButtons),
+    DeviceConnected,
+
+    // The effect was turned on or off, optionally only in one direction
+    EffectToggled(RuleEffect, Option<bool>),
+
+    // The channel volume moved above or below the threshold
+    VolumeAbove(ChannelName, u8),
+    VolumeBelow(ChannelName, u8),
+}
+
+#[derive(Debug, Copy, Clone, Enum, Serialize, Deserialize, PartialEq, Eq)]
+pub enum RuleEffect {
+    Fx,
+    Megaphone,
+    Robot,
+    HardTune,
+}
+
+// Command lists bound to a button, these replace the button's own hold behaviour, and a double
+// press replaces two single presses. The release commands run when the button is let go after
+// the hold commands have been run.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ButtonBinding {
+    pub hold: Vec<GoXLRCommand>,
+    pub double_press: Vec<GoXLRCommand>,
+
+    #[serde(default)]
+    pub release: Vec<GoXLRCommand>,
+}
+
+impl ButtonBinding {
+    pub fn is_empty(&self) -> bool {
+        self.hold.is_empty() && self.double_press.is_empty() && self.release.is_empty()
+    }
+}
+
+// A saved mixer state, volumes are crossfaded to when a scene is recalled. The submix is None
+// if submixing wasn't enabled, and mute states are only present for channels on a fader. Channel
+// mutes are the mutes set directly on a channel, they're None for scenes saved before these were
+// captured, in which case they're left alone on recall.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Scene {
+    pub volumes: EnumMap<ChannelName, u8>,
+    pub submix: Option<EnumMap<SubMixChannelName, u8>>,
+    pub mute_states: EnumMap<ChannelName, Option<MuteState>>,
+
+    #[serde(default)]
+    pub channel_mutes: Option<EnumMap<ChannelName, Option<MuteFunction>>>,
+    pub routing: EnumMap<InputDevice, EnumMap<OutputDevice, bool>>,
+}
+
+// Voice Activity Detection, the mic level needs to stay above the threshold (in dB) for the
+// attack time before speaking is flagged, and below it for the hold time before it's cleared.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct VoiceActivity {
+    pub enabled: bool,
+    pub threshold: i8,
+    pub attack: u16,
+    pub hold: u16,
+}
+
+impl Default for VoiceActivity {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold: -40,
+            attack: 50,
+            hold: 500,
+        }
+    }
+}
+
+// Ducking lowers the target channels by `amount` percent while the source is active, ramping
+// down over the attack time (in ms) and back up over the release time. `input` is the channel
+// watched when the source is an Input.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Ducking {
+    pub enabled: bool,
+    pub source: DuckingSource,
+
+    #[serde(default = "default_ducking_input")]
+    pub input: ChannelName,
+    pub targets: Vec<ChannelName>,
+    pub amount: u8,
+    pub attack: u16,
+    pub release: u16,
+}
+
+impl Default for Ducking {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            source: DuckingSource::Microphone,
+            input: default_ducking_input(),
+            targets: vec![ChannelName::Music, ChannelName::Game],
+            amount: 50,
+            attack: 100,
+            release: 800,
+        }
+    }
+}
+
+fn default_ducking_input() -> ChannelName {
+    ChannelName::Chat
+}
+
+// Push to Talk keeps the mic muted (using the cough button's mute function) except while the
+// cough button is held, re-muting `release_tail` ms after it's let go. If `latch` is set, a
+// double press holds the mic open until the button is next pressed.
+#[derive(Debug, Copy, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PushToTalk {
+    pub enabled: bool,
+    pub release_tail: u16,
+    pub latch: bool,
+}
+
+impl Default for PushToTalk {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            release_tail: 200,
+            latch: false,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
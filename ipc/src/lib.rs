@@ -9,14 +9,14 @@ mod device;
 
 pub use device::*;
 use goxlr_types::{
-    AnimationMode, Button, ButtonColourGroups, ButtonColourOffStyle, ChannelName,
+    AnimationMode, Button, ButtonAction, ButtonColourGroups, ButtonColourOffStyle, ChannelName,
     CompressorAttackTime, CompressorRatio, CompressorReleaseTime, DeviceType, DisplayMode,
-    DisplayModeComponents, EchoStyle, EffectBankPresets, EncoderColourTargets, EqFrequencies,
-    FaderDisplayStyle, FaderName, GateTimes, GenderStyle, HardTuneSource, HardTuneStyle,
-    InputDevice, MegaphoneStyle, MicrophoneType, MiniEqFrequencies, Mix, MuteFunction, MuteState,
-    OutputDevice, PitchStyle, ReverbStyle, RobotRange, RobotStyle, SampleBank, SampleButtons,
-    SamplePlayOrder, SamplePlaybackMode, SamplerColourTargets, SimpleColourTargets, VersionNumber,
-    VodMode, WaterfallDirection,
+    DisplayModeComponents, DuckingSource, EchoStyle, EffectBankPresets, EncoderColourTargets,
+    EqFrequencies, FaderDisplayStyle, FaderName, GateTimes, GenderStyle, HardTuneSource,
+    HardTuneStyle, InputDevice, MegaphoneStyle, MicrophoneType, MiniEqFrequencies, Mix,
+    MuteFunction, MuteState, OutputDevice, PitchStyle, ReverbStyle, RobotRange, RobotStyle,
+    SampleBank, SampleButtons, SamplePlayOrder, SamplePlaybackMode, SamplerColourTargets,
+    SimpleColourTargets, VersionNumber, VodMode, VolumeCurve, WaterfallDirection,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,7 +25,23 @@ pub enum DaemonRequest {
     GetStatus,
     Daemon(DaemonCommand),
     GetMicLevel(String),
+
+    // Streams MicLevelMeter responses for a device at the requested rate (in Hz), these are
+    // only available on persistent connections (the IPC Socket, and the WebSocket).
+    SubscribeMicLevel(String, u8),
+    UnsubscribeMicLevel(String),
+
+    // Turns an IPC Socket connection into a stream of Patch responses, replies with the current
+    // Status which the Patches should be applied to. The WebSocket always receives Patches.
+    SubscribeEvents,
+    UnsubscribeEvents,
+
     Command(String, GoXLRCommand),
+
+    // Runs the commands together with a single status update, if any command fails the profile
+    // is rolled back to how it was before the batch. Commands which write to disk, or change state
+    // outside of the profile (timers, ramps, playback, etc) are refused.
+    Batch(String, Vec<GoXLRCommand>),
     RunFirmwareUpdate(String, Option<PathBuf>, bool),
     ContinueFirmwareUpdate(String),
     ClearFirmwareState(String),
@@ -37,10 +53,21 @@ pub enum DaemonResponse {
     Ok,
     Error(String),
     MicLevel(f64),
+    MicLevelMeter(MicLevelMeter),
     Status(DaemonStatus),
     Patch(Patch),
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MicLevelMeter {
+    pub serial: String,
+
+    // All levels are in dB, between -72.2 and 0
+    pub level: f64,
+    pub peak: f64,
+    pub clipped: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WebsocketRequest {
     pub id: u64,
@@ -132,6 +159,18 @@ pub enum DaemonCommand {
     ApplySampleChange,
 
     HandleMacOSAggregates(bool),
+
+    // App Rules for a device (by serial), setting a rule replaces any with the same name
+    SetAppRule(String, String, AppRule),
+    DeleteAppRule(String, String),
+
+    // Scheduled Tasks, setting a task replaces any with the same name
+    SetScheduledTask(String, ScheduledTask),
+    DeleteScheduledTask(String),
+
+    // Link Groups, setting a group replaces any with the same name
+    SetLinkGroup(String, LinkGroup),
+    DeleteLinkGroup(String),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -139,18 +178,38 @@ pub enum GoXLRCommand {
     SetShutdownCommands(Vec<GoXLRCommand>),
     SetSleepCommands(Vec<GoXLRCommand>),
     SetWakeCommands(Vec<GoXLRCommand>),
+    SetLockCommands(Vec<GoXLRCommand>),
+    SetUnlockCommands(Vec<GoXLRCommand>),
+    SetButtonHoldCommands(Button, Vec<GoXLRCommand>),
+
+    // Not available on buttons which act as soon as they're pressed (Cough, Bleep and the
+    // Sampler buttons), waiting to see if a second press is coming would delay every press.
+    SetButtonDoublePressCommands(Button, Vec<GoXLRCommand>),
+    SetButtonReleaseCommands(Button, Vec<GoXLRCommand>),
+
+    // Acts on a button as if it had been used on the hardware
+    TriggerButton(Button, ButtonAction),
     SetSamplerPreBufferDuration(u16),
 
     SetFader(FaderName, ChannelName),
     SetFaderMuteFunction(FaderName, MuteFunction),
 
+    // Mutes to a custom set of outputs rather than the Mute Function, an empty list goes back
+    // to the Mute Function.
+    SetFaderMuteTargets(FaderName, Vec<OutputDevice>),
+
     SetVolume(ChannelName, u8),
+    // Moves the volume up or down by a percentage of the full range
+    AdjustVolume(ChannelName, i8),
+    // Moves to a target volume over a duration (in milliseconds)
+    RampVolume(ChannelName, u8, u32, VolumeCurve),
     SetMicrophoneType(MicrophoneType),
     SetMicrophoneGain(MicrophoneType, u16),
     SetRouter(InputDevice, OutputDevice, bool),
 
     // Cough Button
     SetCoughMuteFunction(MuteFunction),
+    SetCoughMuteTargets(Vec<OutputDevice>),
     SetCoughIsHold(bool),
 
     // Bleep Button
@@ -285,6 +344,21 @@ pub enum GoXLRCommand {
     SetScribbleNumber(FaderName, String),
     SetScribbleInvert(FaderName, bool),
 
+    // History, these undo and redo changes to the profile and mic profile
+    Undo(),
+    Redo(),
+    ClearHistory(),
+
+    // Scenes, recalling a scene fades the volumes over the duration (in milliseconds)
+    SaveScene(String),
+    RecallScene(String, u32),
+    DeleteScene(String),
+
+    // Rules, setting a rule replaces any existing rule with the same name
+    SetRule(String, Rule),
+    SetRuleEnabled(String, bool),
+    DeleteRule(String),
+
     // Profile Handling..
     NewProfile(String),
     LoadProfile(String, bool),
@@ -303,12 +377,34 @@ pub enum GoXLRCommand {
     // General Settings
     SetMuteHoldDuration(u16),
     SetVCMuteAlsoMuteCM(bool),
+    SetPanicMutesChat(bool),
     SetMonitorWithFx(bool),
     SetSamplerResetOnClear(bool),
     SetSamplerFadeDuration(u32),
     SetLockFaders(bool),
     SetVodMode(VodMode),
 
+    // Voice Activity Detection
+    SetVoiceActivityEnabled(bool),
+    SetVoiceActivityThreshold(i8),
+    SetVoiceActivityAttack(u16),
+    SetVoiceActivityHold(u16),
+
+    // Ducking
+    SetDuckingEnabled(bool),
+    SetDuckingSource(DuckingSource),
+    SetDuckingInput(ChannelName),
+    SetDuckingTarget(ChannelName, bool),
+    SetDuckingAmount(u8),
+    SetDuckingAttack(u16),
+    SetDuckingRelease(u16),
+    SetDuckingTriggered(bool),
+
+    // Push to Talk
+    SetPushToTalkEnabled(bool),
+    SetPushToTalkReleaseTail(u16),
+    SetPushToTalkLatch(bool),
+
     // These control the current GoXLR 'State'..
     SetActiveEffectPreset(EffectBankPresets),
     SetActiveSamplerBank(SampleBank),
@@ -319,9 +415,21 @@ pub enum GoXLRCommand {
     SetFaderMuteState(FaderName, MuteState),
     SetCoughMuteState(MuteState),
 
+    // Removes every input from the stream mixes (and Chat Mic, if set) until turned off again
+    SetPanicActive(bool),
+
+    // Mutes for a number of seconds before unmuting again, optionally announcing it via TTS
+    // shortly before and once unmuted. A duration of 0 cancels the timer, leaving the mute.
+    SetFaderMuteTimer(FaderName, u32, bool),
+    SetCoughMuteTimer(u32, bool),
+
+    // Mutes a channel regardless of whether it's on a fader, None unmutes it
+    SetChannelMute(ChannelName, Option<MuteFunction>),
+
     // Submix Commands
     SetSubMixEnabled(bool),
     SetSubMixVolume(ChannelName, u8),
+    RampSubMixVolume(ChannelName, u8, u32, VolumeCurve),
     SetSubMixLinked(ChannelName, bool),
     SetSubMixOutputMix(OutputDevice, Mix),
 
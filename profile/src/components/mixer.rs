@@ -5,11 +5,13 @@ use enum_map::{Enum, EnumMap};
 use strum::{EnumIter, EnumProperty, IntoEnumIterator};
 
 use anyhow::Result;
+use log::warn;
 use quick_xml::Writer;
 use quick_xml::events::{BytesStart, Event};
 
 use crate::components::colours::ColourMap;
 use crate::components::mixer::FullChannelList::LineOut;
+use crate::components::mute::MuteFunction;
 use crate::profile::Attribute;
 
 #[derive(thiserror::Error, Debug)]
@@ -34,6 +36,7 @@ type RoutingTable = EnumMap<InputChannels, EnumMap<OutputChannels, u16>>;
 pub struct Mixers {
     mixer_table: RoutingTable,
     volume_table: EnumMap<FullChannelList, u8>,
+    mute_table: EnumMap<FullChannelList, Option<MuteFunction>>,
     colour_map: ColourMap,
 }
 
@@ -92,6 +95,7 @@ impl Mixers {
         Self {
             mixer_table,
             volume_table,
+            mute_table: EnumMap::default(),
             colour_map: ColourMap::new("mixerTree".to_string()),
         }
     }
@@ -122,6 +126,21 @@ impl Mixers {
                 continue;
             }
 
+            // Channel mutes aren't part of the official format, so only exist when set
+            if let Some(channel) = attr.name.strip_suffix("MuteFunction") {
+                let function = MuteFunction::iter()
+                    .find(|function| function.get_str("Value").unwrap() == attr.value);
+                let mute =
+                    FullChannelList::iter().find(|mute| mute.get_str("Name").unwrap() == channel);
+
+                match (mute, function) {
+                    (Some(mute), Some(function)) => self.mute_table[mute] = Some(function),
+                    (None, _) => warn!("Couldn't find Mute Channel: {}", channel),
+                    (_, None) => warn!("Couldn't find Mute Function: {}", attr.value),
+                }
+                continue;
+            }
+
             if attr.name.contains("To") {
                 // Extract the two sides of the string..
                 let name = attr.name.as_str();
@@ -171,6 +190,15 @@ impl Mixers {
             attributes.insert(key, value);
         }
 
+        for channel in FullChannelList::iter() {
+            if let Some(function) = self.mute_table[channel] {
+                let key = format!("{}MuteFunction", channel.get_str("Name").unwrap());
+                let value = function.get_str("Value").unwrap().to_string();
+
+                attributes.insert(key, value);
+            }
+        }
+
         for input in InputChannels::iter() {
             // Get the map for this channel..
             let input_text = input.get_str("Name").unwrap();
@@ -212,6 +240,14 @@ impl Mixers {
         self.volume_table[channel] = volume;
         Ok(())
     }
+
+    pub fn channel_mute(&self, channel: FullChannelList) -> Option<MuteFunction> {
+        self.mute_table[channel]
+    }
+
+    pub fn set_channel_mute(&mut self, channel: FullChannelList, function: Option<MuteFunction>) {
+        self.mute_table[channel] = function;
+    }
 }
 
 #[derive(Debug, EnumIter, Enum, EnumProperty, Clone, Copy)]
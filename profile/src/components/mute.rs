@@ -11,6 +11,7 @@ use quick_xml::events::{BytesStart, Event};
 
 use crate::Faders;
 use crate::components::colours::{Colour, ColourMap, ColourOffStyle};
+use crate::components::mixer::OutputChannels;
 use crate::profile::Attribute;
 
 #[derive(thiserror::Error, Debug)]
@@ -35,6 +36,10 @@ pub struct MuteButton {
     mute_function: MuteFunction,
     previous_volume: u8,
 
+    // A custom set of outputs to mute to, the mute function holds the nearest legacy value so
+    // the official app still has something sensible to work with.
+    mute_targets: Option<Vec<OutputChannels>>,
+
     // Labelled as 'fromMuteAllFlag' in the XML, honestly, not sure what this does either,
     // it's either 1, 0 or simply not there.
     from_mute_all: Option<bool>,
@@ -56,6 +61,7 @@ impl MuteButton {
             colour_map,
             mute_function: MuteFunction::All,
             previous_volume: 0,
+            mute_targets: None,
 
             from_mute_all: None,
         }
@@ -94,6 +100,11 @@ impl MuteButton {
                 continue;
             }
 
+            if attr.name.ends_with("Targets") {
+                self.mute_targets = Some(parse_mute_targets(&attr.value));
+                continue;
+            }
+
             if attr.name.ends_with("prevLevel") {
                 // Simple, parse this into a u8 :)
                 let value: u8 = attr.value.parse()?;
@@ -135,6 +146,13 @@ impl MuteButton {
             format!("{}", self.previous_volume),
         );
 
+        if let Some(targets) = &self.mute_targets {
+            attributes.insert(
+                format!("{element_name}Targets"),
+                write_mute_targets(targets),
+            );
+        }
+
         if let Some(from_mute_all) = self.from_mute_all {
             attributes.insert(
                 "fromMuteAllFlag".to_string(),
@@ -167,6 +185,13 @@ impl MuteButton {
         self.mute_function = mute_function;
     }
 
+    pub fn mute_targets(&self) -> Option<&Vec<OutputChannels>> {
+        self.mute_targets.as_ref()
+    }
+    pub fn set_mute_targets(&mut self, mute_targets: Option<Vec<OutputChannels>>) {
+        self.mute_targets = mute_targets;
+    }
+
     pub fn set_previous_volume(&mut self, previous_volume: u8) -> Result<()> {
         self.previous_volume = previous_volume;
         Ok(())
@@ -176,6 +201,26 @@ impl MuteButton {
     }
 }
 
+// Mute targets are stored as a comma separated list of output names
+pub fn parse_mute_targets(value: &str) -> Vec<OutputChannels> {
+    let mut targets = vec![];
+    for name in value.split(',').filter(|name| !name.is_empty()) {
+        match OutputChannels::iter().find(|output| output.get_str("Name").unwrap() == name) {
+            Some(output) => targets.push(output),
+            None => warn!("Couldn't find Mute Target: {}", name),
+        }
+    }
+    targets
+}
+
+pub fn write_mute_targets(targets: &[OutputChannels]) -> String {
+    targets
+        .iter()
+        .map(|output| output.get_str("Name").unwrap())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
 // MuteChat
 #[derive(Debug, Display, Copy, Clone, Enum, EnumProperty, EnumIter, PartialEq, Eq)]
 pub enum MuteFunction {
@@ -7,7 +7,8 @@ use strum::EnumProperty;
 use anyhow::{Result, anyhow};
 
 use crate::components::colours::{Colour, ColourMap, ColourOffStyle, ColourState};
-use crate::components::mute::MuteFunction;
+use crate::components::mixer::OutputChannels;
+use crate::components::mute::{MuteFunction, parse_mute_targets, write_mute_targets};
 use crate::components::mute_chat::CoughToggle::Hold;
 
 #[derive(thiserror::Error, Debug)]
@@ -45,6 +46,7 @@ pub struct MuteChat {
     blink: ColourState,
     cough_behaviour: CoughToggle,
     cough_mute_source: MuteFunction,
+    cough_mute_targets: Option<Vec<OutputChannels>>,
     cough_button_on: bool,
 }
 
@@ -65,6 +67,7 @@ impl MuteChat {
             blink: ColourState::Off,
             cough_behaviour: Hold,
             cough_mute_source: MuteFunction::All,
+            cough_mute_targets: None,
             cough_button_on: false,
         }
     }
@@ -90,6 +93,11 @@ impl MuteChat {
                 continue;
             }
 
+            if attr.name == "coughButtonMuteTargets" {
+                self.cough_mute_targets = Some(parse_mute_targets(&attr.value));
+                continue;
+            }
+
             if attr.name == "coughButtonIsOn" {
                 self.cough_button_on = attr.value != "0";
                 continue;
@@ -132,6 +140,12 @@ impl MuteChat {
                 .unwrap()
                 .to_string(),
         );
+        if let Some(targets) = &self.cough_mute_targets {
+            attributes.insert(
+                "coughButtonMuteTargets".to_string(),
+                write_mute_targets(targets),
+            );
+        }
         attributes.insert(
             "coughButtonIsOn".to_string(),
             if self.cough_button_on {
@@ -196,6 +210,14 @@ impl MuteChat {
     pub fn set_cough_mute_source(&mut self, cough_mute_source: MuteFunction) {
         self.cough_mute_source = cough_mute_source;
     }
+    pub fn cough_mute_targets(&self) -> Option<&Vec<OutputChannels>> {
+        self.cough_mute_targets.as_ref()
+    }
+
+    pub fn set_cough_mute_targets(&mut self, targets: Option<Vec<OutputChannels>>) {
+        self.cough_mute_targets = targets;
+    }
+
     pub fn set_cough_button_on(&mut self, cough_button_on: bool) {
         self.cough_button_on = cough_button_on;
     }
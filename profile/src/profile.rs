@@ -1,6 +1,6 @@
 use std::fs;
 use std::fs::File;
-use std::io::{BufReader, Read, Write};
+use std::io::{BufReader, Read, Seek, Write};
 use std::path::Path;
 use std::str::FromStr;
 
@@ -51,7 +51,7 @@ pub struct Attribute {
 }
 
 impl Profile {
-    pub fn load<R: Read + std::io::Seek>(read: R) -> Result<Self> {
+    pub fn load<R: Read + Seek>(read: R) -> Result<Self> {
         debug!("Loading Profile Archive..");
 
         let mut archive = zip::ZipArchive::new(read)?;
@@ -92,9 +92,28 @@ impl Profile {
 
         debug!("Creating Temporary Save File: {:?}", &tmp_file_name);
         let temp_file = File::create(&tmp_file_name)?;
+        self.write_to(&temp_file)?;
 
-        // Create a new ZipFile at the requested location
-        let mut archive = zip::ZipWriter::new(&temp_file);
+        // The archive has finished writing, we don't need it anymore (keeping it live prevents
+        // us from removing the temporary file).
+        temp_file.sync_all()?;
+
+        // Once complete, we simply move the file over the existing file..
+        debug!("Save Complete and synced, renaming to {:?}", path.as_ref());
+        if path.as_ref().exists() {
+            debug!("Target profile exists, removing..");
+            fs::remove_file(&path).unwrap_or_else(|e| {
+                warn!("Error Removing File: {}", e);
+            });
+        }
+        debug!("Renaming {:?} to {:?}", tmp_file_name, path.as_ref());
+        fs::rename(tmp_file_name, &path)?;
+        Ok(())
+    }
+
+    pub fn write_to<W: Write + Seek>(&mut self, sink: W) -> Result<()> {
+        // Create a new ZipFile inside the sink
+        let mut archive = zip::ZipWriter::new(sink);
 
         // Store the profile..
         archive.start_file("profile.xml", SimpleFileOptions::default())?;
@@ -110,21 +129,6 @@ impl Profile {
             }
         }
         archive.finish()?;
-
-        // The archive has finished writing, we don't need it anymore (keeping it live prevents
-        // us from removing the temporary file).
-        temp_file.sync_all()?;
-
-        // Once complete, we simply move the file over the existing file..
-        debug!("Save Complete and synced, renaming to {:?}", path.as_ref());
-        if path.as_ref().exists() {
-            debug!("Target profile exists, removing..");
-            fs::remove_file(&path).unwrap_or_else(|e| {
-                warn!("Error Removing File: {}", e);
-            });
-        }
-        debug!("Renaming {:?} to {:?}", tmp_file_name, path.as_ref());
-        fs::rename(tmp_file_name, &path)?;
         Ok(())
     }
 
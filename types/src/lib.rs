@@ -416,7 +416,9 @@ pub enum FaderDisplayStyle {
     GradientMeter,
 }
 
-#[derive(Debug, Copy, Clone, Display, Enum, EnumIter, EnumCount, PartialEq, Eq, Hash)]
+#[derive(
+    Debug, Copy, Clone, Display, Enum, EnumIter, EnumCount, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Button {
@@ -907,6 +909,48 @@ pub enum VodMode {
     StreamNoMusic,
 }
 
+#[derive(Default, Debug, Copy, Clone, EnumIter, Display, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(ValueEnum))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DuckingSource {
+    // Duck while Voice Activity Detection reports the Microphone as speaking
+    #[default]
+    Microphone,
+
+    // Duck while the chosen input channel is active (unmuted, or for Samples, while playing)
+    Input,
+
+    // Duck only while triggered over IPC (for example, by a tool watching another input)
+    Manual,
+}
+
+#[derive(Default, Debug, Copy, Clone, EnumIter, Display, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(ValueEnum))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum VolumeCurve {
+    // Moves at a constant rate
+    #[default]
+    Linear,
+
+    // Moves quickly at the start and slows down towards the target
+    Logarithmic,
+
+    // Eases in and out of the ramp
+    SCurve,
+}
+
+#[derive(Debug, Copy, Clone, EnumIter, Display, PartialEq, Eq)]
+#[cfg_attr(feature = "clap", derive(ValueEnum))]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ButtonAction {
+    // The button goes down, and stays down until released
+    Press,
+    Release,
+
+    // The button is pressed, and released again once the hold has been handled
+    Hold,
+}
+
 #[derive(Default, Debug, Copy, Clone, Enum, PartialEq, Eq)]
 #[cfg_attr(feature = "clap", derive(ValueEnum))]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -0,0 +1,95 @@
+/*
+    Prints the GoXLR requests (and their responses) from a usbmon text dump, a pcap file or a
+    daemon capture, decoding the payloads where possible. Usage:
+
+    goxlr-usb-decode <file> [file...]
+*/
+
+use anyhow::{Result, bail};
+use goxlr_usb::commands::Command;
+use goxlr_usb::decoder::{
+    Payload, Transfer, decode_request, format_microphone_value, load_transfers,
+};
+use goxlr_usb::device::capture::to_hex;
+use std::path::PathBuf;
+
+fn main() -> Result<()> {
+    let files: Vec<PathBuf> = std::env::args_os().skip(1).map(PathBuf::from).collect();
+    if files.is_empty() {
+        bail!("Usage: goxlr-usb-decode <usbmon text | pcap | capture> [file...]");
+    }
+
+    for file in files {
+        println!("== {}", file.to_string_lossy());
+        for transfer in load_transfers(&file)? {
+            print_transfer(&transfer);
+        }
+    }
+    Ok(())
+}
+
+fn print_transfer(transfer: &Transfer) {
+    let truncated = if transfer.truncated {
+        " (truncated)"
+    } else {
+        ""
+    };
+    let Some(command) = transfer.command() else {
+        println!(
+            "{:>12.6} #{:<5} Unknown (0x{:x}) {}{}",
+            transfer.time,
+            transfer.command_index,
+            transfer.command_id,
+            to_hex(&transfer.request),
+            truncated
+        );
+        return;
+    };
+
+    println!(
+        "{:>12.6} #{:<5} {:?} (0x{:x}){}",
+        transfer.time, transfer.command_index, command, transfer.command_id, truncated
+    );
+    print_payload(command, &transfer.request);
+
+    if let Some(response) = &transfer.response
+        && !response.is_empty()
+    {
+        println!("{:>20} {}", "<-", to_hex(response));
+    }
+}
+
+fn print_payload(command: Command, body: &[u8]) {
+    let indent = 21;
+    match decode_request(command, body) {
+        Ok(Payload::Empty) => {}
+        Ok(Payload::Raw(data)) => println!("{:indent$}{}", "", to_hex(&data)),
+        Ok(Payload::EffectParameters(effects)) => {
+            for (key, value) in effects {
+                println!("{:indent$}{:?}: {}", "", key, value);
+            }
+        }
+        Ok(Payload::MicrophoneParameters(params)) => {
+            for (key, value) in params {
+                let formatted = format_microphone_value(key, value);
+                println!("{:indent$}{:?}: {}", "", key, formatted);
+            }
+        }
+        Ok(Payload::Routing(routing)) => {
+            for (output, value) in routing {
+                println!("{:indent$}{:?}: 0x{:02x}", "", output, value);
+            }
+        }
+        Ok(Payload::ColourMap(colours)) => {
+            for (target, values) in colours {
+                // Colours are sent as BGRA, display them as RGB
+                let values: Vec<String> = values
+                    .iter()
+                    .map(|value| format!("{:02X}{:02X}{:02X}", value[2], value[1], value[0]))
+                    .collect();
+                println!("{:indent$}{:?}: {}", "", target, values.join(" "));
+            }
+        }
+        Err(error) => println!("{:indent$}{} ({})", "", to_hex(body), error),
+    }
+}
@@ -1,5 +1,6 @@
 use crate::routing::InputDevice;
 use goxlr_types::{ChannelName, EncoderName, FaderName, SubMixChannelName};
+use strum::{EnumIter, IntoEnumIterator};
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Command {
@@ -66,9 +67,48 @@ impl Command {
             Command::ExecuteFirmwareUpdateAction(sub) => (0x004 << 12) | sub.id(),
         }
     }
+
+    /// Maps a command id (as sent over USB) back to its Command, if it's one we know about
+    pub fn from_command_id(id: u32) -> Option<Command> {
+        let sub = id & 0xfff;
+        let command = match id >> 12 {
+            0x000 if sub == 0 => Command::ResetCommandIndex,
+            0x000 => Command::SystemInfo(find_sub(sub, SystemInfoCommand::id)?),
+            0x004 => Command::ExecuteFirmwareUpdateAction(find_sub(sub, FirmwareAction::id)?),
+            0x800 if sub == 0 => Command::GetButtonStates,
+            0x801 if sub == 0 => Command::SetEffectParameters,
+            0x802 => Command::SetScribble(find_sub(sub, |fader| *fader as u32)?),
+            0x803 if sub == 0 => Command::SetColourMap(),
+            0x804 => Command::SetRouting(find_sub(sub, |input: &InputDevice| input.id() as u32)?),
+            0x805 => Command::SetFader(find_sub(sub, |fader| *fader as u32)?),
+            0x806 if sub >= 16 => {
+                Command::SetSubChannelVolume(find_sub(sub - 16, |channel| *channel as u32)?)
+            }
+            0x806 => Command::SetChannelVolume(find_sub(sub, |channel| *channel as u32)?),
+            0x808 if sub == 0 => Command::SetButtonStates(),
+            0x809 => Command::SetChannelState(find_sub(sub, |channel| *channel as u32)?),
+            0x80a => Command::SetEncoderValue(find_sub(sub, |encoder| *encoder as u32)?),
+            0x80b if sub == 0 => Command::SetMicrophoneParameters,
+            0x80c if sub == 0 => Command::GetMicrophoneLevel,
+            0x80f => Command::GetHardwareInfo(find_sub(sub, |info| *info as u32)?),
+            0x810 => Command::ExecuteFirmwareUpdateCommand(find_sub(sub, |cmd| *cmd as u32)?),
+            0x811 => Command::SetEncoderMode(find_sub(sub, |encoder| *encoder as u32)?),
+            0x814 => Command::SetFaderDisplayMode(find_sub(sub, |fader| *fader as u32)?),
+            0x816 if sub == 0 => Command::SetAnimationMode,
+            0x817 if sub == 0 => Command::SetChannelMixes,
+            0x818 if sub == 0 => Command::SetMonitoredMix,
+            _ => return None,
+        };
+        Some(command)
+    }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+// Finds the sub command (or target) which has the given id
+fn find_sub<T: IntoEnumIterator>(sub: u32, id: impl Fn(&T) -> u32) -> Option<T> {
+    T::iter().find(|value| id(value) == sub)
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, EnumIter)]
 pub enum SystemInfoCommand {
     FirmwareVersion,
     SupportsDCPCategory,
@@ -83,13 +123,13 @@ impl SystemInfoCommand {
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, EnumIter)]
 pub enum HardwareInfoCommand {
     FirmwareVersion = 0,
     SerialNumber = 1,
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, EnumIter)]
 pub enum FirmwareCommand {
     // Start the update (Makes GoXLR go green, we should lock the util here.)
     START,
@@ -111,7 +151,7 @@ pub enum FirmwareCommand {
 }
 
 // DCP Commands for managing a firmware update (0x004)
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, EnumIter)]
 pub enum FirmwareAction {
     // Formats and erases the update partition
     ERASE,
@@ -136,3 +176,32 @@ impl FirmwareAction {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_ids_round_trip() {
+        let commands = [
+            Command::ResetCommandIndex,
+            Command::SystemInfo(SystemInfoCommand::FirmwareVersion),
+            Command::SetChannelVolume(ChannelName::Headphones),
+            Command::SetSubChannelVolume(SubMixChannelName::Mic),
+            Command::SetRouting(InputDevice::MicrophoneLeft),
+            Command::SetEncoderMode(EncoderName::Echo),
+            Command::GetHardwareInfo(HardwareInfoCommand::SerialNumber),
+            Command::GetMicrophoneLevel,
+            Command::ExecuteFirmwareUpdateAction(FirmwareAction::VALIDATE),
+        ];
+
+        for command in commands {
+            assert_eq!(
+                Command::from_command_id(command.command_id()),
+                Some(command)
+            );
+        }
+        assert_eq!(Command::from_command_id(0x800 << 12 | 1), None);
+        assert_eq!(Command::from_command_id(0xfff << 12), None);
+    }
+}
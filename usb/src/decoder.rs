@@ -0,0 +1,755 @@
+// Decoding of GoXLR vendor requests from USB captures. This reads usbmon text output, pcap
+// files (from either usbmon or USBPcap) or the daemon's own capture files, pairs each request
+// with its response, and turns the payloads back into their typed values using the same
+// definitions the daemon uses to build them, so the two can't drift apart.
+use crate::colouring::ColourTargets;
+use crate::commands::Command;
+use crate::device::capture::{CaptureRecord, from_hex, load_capture};
+use crate::routing::OutputDevice;
+use anyhow::{Result, anyhow, bail};
+use byteorder::{ByteOrder, LittleEndian};
+use goxlr_types::{EffectKey, MicrophoneParamKey};
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use strum::IntoEnumIterator;
+
+// The vendor requests used to send a command, and read back its response
+const REQUEST_WRITE: u8 = 2;
+const REQUEST_READ: u8 = 3;
+
+// Pcap Link Types
+const LINKTYPE_USB_LINUX: u32 = 189;
+const LINKTYPE_USB_LINUX_MMAPPED: u32 = 220;
+const LINKTYPE_USBPCAP: u32 = 249;
+
+/// A single request to the GoXLR, paired with its response (if one was captured)
+#[derive(Debug, Clone)]
+pub struct Transfer {
+    /// Time of the request in seconds, relative to the start of the capture
+    pub time: f64,
+    pub command_id: u32,
+    pub command_index: u16,
+    pub request: Vec<u8>,
+    pub response: Option<Vec<u8>>,
+
+    /// Set if the capture didn't contain the full request (usbmon text is limited to 32 bytes)
+    pub truncated: bool,
+}
+
+impl Transfer {
+    pub fn command(&self) -> Option<Command> {
+        Command::from_command_id(self.command_id)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Payload {
+    Empty,
+    Raw(Vec<u8>),
+    EffectParameters(Vec<(EffectKey, i32)>),
+    MicrophoneParameters(Vec<(MicrophoneParamKey, [u8; 4])>),
+    Routing(Vec<(OutputDevice, u8)>),
+    ColourMap(Vec<(ColourTargets, Vec<[u8; 4]>)>),
+}
+
+/// Decodes the body of a request into its typed values, where we know how to
+pub fn decode_request(command: Command, body: &[u8]) -> Result<Payload> {
+    if body.is_empty() {
+        return Ok(Payload::Empty);
+    }
+
+    Ok(match command {
+        Command::SetEffectParameters => Payload::EffectParameters(decode_effect_parameters(body)?),
+        Command::SetMicrophoneParameters => {
+            Payload::MicrophoneParameters(decode_microphone_parameters(body)?)
+        }
+        Command::SetRouting(_) => Payload::Routing(decode_routing(body)?),
+        Command::SetColourMap() => Payload::ColourMap(decode_colour_map(body)?),
+        _ => Payload::Raw(body.to_vec()),
+    })
+}
+
+pub fn decode_effect_parameters(body: &[u8]) -> Result<Vec<(EffectKey, i32)>> {
+    if !body.len().is_multiple_of(8) {
+        bail!(
+            "Effect Parameters should be a multiple of 8 bytes, got {}",
+            body.len()
+        );
+    }
+
+    body.chunks_exact(8)
+        .map(|chunk| {
+            let id = LittleEndian::read_u32(&chunk[0..4]);
+            let key = EffectKey::iter()
+                .find(|key| *key as u32 == id)
+                .ok_or_else(|| anyhow!("Unknown Effect Key: 0x{:x}", id))?;
+            Ok((key, LittleEndian::read_i32(&chunk[4..8])))
+        })
+        .collect()
+}
+
+pub fn decode_microphone_parameters(body: &[u8]) -> Result<Vec<(MicrophoneParamKey, [u8; 4])>> {
+    if !body.len().is_multiple_of(8) {
+        bail!(
+            "Microphone Parameters should be a multiple of 8 bytes, got {}",
+            body.len()
+        );
+    }
+
+    body.chunks_exact(8)
+        .map(|chunk| {
+            let id = LittleEndian::read_u32(&chunk[0..4]);
+            let key = MicrophoneParamKey::iter()
+                .find(|key| *key as u32 == id)
+                .ok_or_else(|| anyhow!("Unknown Microphone Parameter: 0x{:x}", id))?;
+            let mut value = [0; 4];
+            value.copy_from_slice(&chunk[4..8]);
+            Ok((key, value))
+        })
+        .collect()
+}
+
+/// Formats a microphone parameter value the same way the daemon builds it
+pub fn format_microphone_value(key: MicrophoneParamKey, value: [u8; 4]) -> String {
+    match key {
+        MicrophoneParamKey::MicType => format!("{}", value[0]),
+        MicrophoneParamKey::DynamicGain
+        | MicrophoneParamKey::CondenserGain
+        | MicrophoneParamKey::JackGain => format!("{}", LittleEndian::read_u16(&value[2..4])),
+        MicrophoneParamKey::BleepLevel => format!("{}", LittleEndian::read_f32(&value) / 65536.),
+        _ => format!("{}", LittleEndian::read_f32(&value)),
+    }
+}
+
+/// Returns the value set for each output, the layout changes when Mix2 is supported
+pub fn decode_routing(body: &[u8]) -> Result<Vec<(OutputDevice, u8)>> {
+    let mix2_enabled = match body.len() {
+        22 => false,
+        26 => true,
+        length => bail!("Unexpected Routing Length: {}", length),
+    };
+
+    Ok(OutputDevice::iter()
+        .filter(|output| {
+            mix2_enabled || !matches!(output, OutputDevice::Mix2Left | OutputDevice::Mix2Right)
+        })
+        .map(|output| (output, body[output.position(mix2_enabled)]))
+        .collect())
+}
+
+/// Returns the colours for each target, as they're sent (Blue, Green, Red, Alpha)
+pub fn decode_colour_map(body: &[u8]) -> Result<Vec<(ColourTargets, Vec<[u8; 4]>)>> {
+    let format_1_3_40 = match body.len() {
+        328 => false,
+        520 => true,
+        length => bail!("Unexpected Colour Map Length: {}", length),
+    };
+
+    Ok(ColourTargets::iter()
+        .map(|target| {
+            let colours = (0..target.get_colour_count())
+                .map(|colour| {
+                    let position = target.position(colour, format_1_3_40);
+                    let mut value = [0; 4];
+                    value.copy_from_slice(&body[position..position + 4]);
+                    value
+                })
+                .collect();
+            (target, colours)
+        })
+        .collect())
+}
+
+/// Loads all the GoXLR transfers from a file, detecting its format from its content
+pub fn load_transfers(path: &Path) -> Result<Vec<Transfer>> {
+    let mut magic = [0; 4];
+    let read = File::open(path)?.read(&mut magic)?;
+
+    match magic {
+        _ if read < 4 => read_usbmon_text(BufReader::new(File::open(path)?)),
+        [0x0a, 0x0d, 0x0d, 0x0a] => bail!("pcapng is not supported, please save as pcap"),
+        [0xd4, 0xc3, 0xb2, 0xa1]
+        | [0xa1, 0xb2, 0xc3, 0xd4]
+        | [0x4d, 0x3c, 0xb2, 0xa1]
+        | [0xa1, 0xb2, 0x3c, 0x4d] => read_pcap(BufReader::new(File::open(path)?)),
+        [b'{', ..] => read_capture(path),
+        _ => read_usbmon_text(BufReader::new(File::open(path)?)),
+    }
+}
+
+/// Converts a capture file (as written by the daemon) into transfers
+pub fn read_capture(path: &Path) -> Result<Vec<Transfer>> {
+    let mut transfers = vec![];
+    for record in load_capture(path)? {
+        if let CaptureRecord::Request {
+            time_ms,
+            command_id,
+            request,
+            response,
+            ..
+        } = record
+        {
+            transfers.push(Transfer {
+                time: time_ms as f64 / 1000.,
+                command_id,
+                command_index: 0,
+                request: from_hex(&request)?,
+                response: response.map(|response| from_hex(&response)).transpose()?,
+                truncated: false,
+            });
+        }
+    }
+    Ok(transfers)
+}
+
+// An individual URB event, taken from either a usbmon text line or a pcap packet
+struct UrbEvent {
+    id: u64,
+    time: f64,
+    submit: bool,
+    setup: Option<[u8; 8]>,
+    length: usize,
+    data: Vec<u8>,
+}
+
+// Pairs the submit / complete events of the vendor requests into transfers
+#[derive(Default)]
+struct TransferBuilder {
+    transfers: Vec<Transfer>,
+    pending_reads: HashSet<u64>,
+    by_index: HashMap<u16, usize>,
+    start_time: Option<f64>,
+}
+
+impl TransferBuilder {
+    fn handle(&mut self, event: UrbEvent) {
+        let start = *self.start_time.get_or_insert(event.time);
+
+        if event.submit {
+            let Some(setup) = event.setup else {
+                return;
+            };
+
+            // Vendor requests only, the host to device write, and the device to host read
+            match (setup[0], setup[1]) {
+                (0x40, REQUEST_WRITE) => {
+                    if event.data.len() < 16 {
+                        return;
+                    }
+                    let command_index = LittleEndian::read_u16(&event.data[6..8]);
+                    self.by_index.insert(command_index, self.transfers.len());
+                    self.transfers.push(Transfer {
+                        time: event.time - start,
+                        command_id: LittleEndian::read_u32(&event.data[0..4]),
+                        command_index,
+                        request: event.data[16..].to_vec(),
+                        response: None,
+                        truncated: event.data.len() < event.length,
+                    });
+                }
+                (0xc0, REQUEST_READ) => {
+                    self.pending_reads.insert(event.id);
+                }
+                _ => {}
+            }
+            return;
+        }
+
+        // Reads which fail (because the response isn't ready) come back without data
+        if !self.pending_reads.remove(&event.id) || event.data.len() < 16 {
+            return;
+        }
+
+        let command_index = LittleEndian::read_u16(&event.data[6..8]);
+        if let Some(position) = self.by_index.get(&command_index)
+            && let Some(transfer) = self.transfers.get_mut(*position)
+        {
+            transfer.response = Some(event.data[16..].to_vec());
+            if event.data.len() < event.length {
+                transfer.truncated = true;
+            }
+        }
+    }
+}
+
+/// Reads the text output of usbmon (for example, /sys/kernel/debug/usb/usbmon/1u)
+pub fn read_usbmon_text<R: BufRead>(reader: R) -> Result<Vec<Transfer>> {
+    let mut builder = TransferBuilder::default();
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let event = parse_usbmon_line(&line)
+            .map_err(|e| anyhow!("Invalid usbmon line {}: {}", index + 1, e))?;
+        if let Some(event) = event {
+            builder.handle(event);
+        }
+    }
+    Ok(builder.transfers)
+}
+
+fn parse_usbmon_line(line: &str) -> Result<Option<UrbEvent>> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    if tokens.len() < 4 {
+        bail!("Not enough fields");
+    }
+
+    // We only care about control transfers
+    if !tokens[3].starts_with('C') {
+        return Ok(None);
+    }
+
+    let id = u64::from_str_radix(tokens[0], 16)?;
+    let time = tokens[1].parse::<u64>()? as f64 / 1_000_000.;
+    let submit = match tokens[2] {
+        "S" => true,
+        "C" => false,
+        _ => return Ok(None),
+    };
+
+    let mut position = 4;
+    let mut setup = None;
+    if tokens.get(position) == Some(&"s") {
+        let fields = tokens
+            .get(position + 1..position + 6)
+            .ok_or_else(|| anyhow!("Incomplete Setup Packet"))?;
+
+        let mut packet = [0; 8];
+        packet[0] = u8::from_str_radix(fields[0], 16)?;
+        packet[1] = u8::from_str_radix(fields[1], 16)?;
+        for (index, field) in fields[2..].iter().enumerate() {
+            let value = u16::from_str_radix(field, 16)?;
+            LittleEndian::write_u16(&mut packet[2 + index * 2..4 + index * 2], value);
+        }
+        setup = Some(packet);
+        position += 6;
+    } else {
+        // Status, which we don't need as failed reads don't carry data
+        position += 1;
+    }
+
+    let length = tokens
+        .get(position)
+        .ok_or_else(|| anyhow!("Missing Data Length"))?
+        .parse::<usize>()?;
+
+    let mut data = vec![];
+    if tokens.get(position + 1) == Some(&"=") {
+        for word in &tokens[position + 2..] {
+            data.extend(from_hex(word)?);
+        }
+    }
+
+    Ok(Some(UrbEvent {
+        id,
+        time,
+        submit,
+        setup,
+        length,
+        data,
+    }))
+}
+
+/// Reads a pcap file captured from usbmon (Linux) or USBPcap (Windows)
+pub fn read_pcap<R: Read>(mut reader: R) -> Result<Vec<Transfer>> {
+    let mut header = [0; 24];
+    reader.read_exact(&mut header)?;
+
+    let (swapped, nanoseconds) = match LittleEndian::read_u32(&header[0..4]) {
+        0xa1b2c3d4 => (false, false),
+        0xd4c3b2a1 => (true, false),
+        0xa1b23c4d => (false, true),
+        0x4d3cb2a1 => (true, true),
+        _ => bail!("Not a pcap file"),
+    };
+    let read_u32 = |data: &[u8]| {
+        let value = LittleEndian::read_u32(data);
+        if swapped { value.swap_bytes() } else { value }
+    };
+
+    let snap_length = read_u32(&header[16..20]);
+    let link_type = read_u32(&header[20..24]);
+    if !matches!(
+        link_type,
+        LINKTYPE_USB_LINUX | LINKTYPE_USB_LINUX_MMAPPED | LINKTYPE_USBPCAP
+    ) {
+        bail!("Unsupported pcap Link Type: {}", link_type);
+    }
+
+    let mut builder = TransferBuilder::default();
+    let mut record = [0; 16];
+    loop {
+        // A clean end of file is the end of the capture, anything partial is truncated.
+        let read = read_fully(&mut reader, &mut record)?;
+        if read == 0 {
+            break;
+        }
+        if read < record.len() {
+            bail!("Truncated pcap Record Header");
+        }
+
+        let seconds = read_u32(&record[0..4]) as f64;
+        let fraction = read_u32(&record[4..8]) as f64;
+        let time = seconds + fraction / if nanoseconds { 1e9 } else { 1e6 };
+
+        let length = read_u32(&record[8..12]);
+        if length > snap_length {
+            bail!("pcap Record Length {} exceeds the Snap Length", length);
+        }
+        let mut packet = vec![0; length as usize];
+        reader
+            .read_exact(&mut packet)
+            .map_err(|e| anyhow!("Truncated pcap Record: {}", e))?;
+
+        let event = match link_type {
+            LINKTYPE_USBPCAP => parse_usbpcap_packet(&packet, time),
+            _ => parse_usbmon_packet(&packet, time, link_type == LINKTYPE_USB_LINUX_MMAPPED),
+        };
+        if let Some(event) = event {
+            builder.handle(event);
+        }
+    }
+    Ok(builder.transfers)
+}
+
+// Reads until the buffer is full or the reader runs out, returning how much was read
+fn read_fully<R: Read>(reader: &mut R, buffer: &mut [u8]) -> Result<usize> {
+    let mut position = 0;
+    while position < buffer.len() {
+        match reader.read(&mut buffer[position..]) {
+            Ok(0) => break,
+            Ok(read) => position += read,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => bail!(e),
+        }
+    }
+    Ok(position)
+}
+
+// The usbmon binary header is in the host's byte order, which is little endian for anything
+// the GoXLR Utility runs on.
+fn parse_usbmon_packet(packet: &[u8], time: f64, mmapped: bool) -> Option<UrbEvent> {
+    let header_length = if mmapped { 64 } else { 48 };
+    if packet.len() < header_length || packet[9] != 2 {
+        return None;
+    }
+
+    let mut setup = None;
+    if packet[14] == 0 {
+        let mut packet_setup = [0; 8];
+        packet_setup.copy_from_slice(&packet[40..48]);
+        setup = Some(packet_setup);
+    }
+
+    Some(UrbEvent {
+        id: LittleEndian::read_u64(&packet[0..8]),
+        time,
+        submit: packet[8] == b'S',
+        setup,
+        length: LittleEndian::read_u32(&packet[32..36]) as usize,
+        data: packet[header_length..].to_vec(),
+    })
+}
+
+fn parse_usbpcap_packet(packet: &[u8], time: f64) -> Option<UrbEvent> {
+    if packet.len() < 28 || packet[22] != 2 {
+        return None;
+    }
+
+    let header_length = LittleEndian::read_u16(&packet[0..2]) as usize;
+    let data = packet.get(header_length..)?;
+    let completion = packet[16] & 0x01 == 0x01;
+    let stage = packet[27];
+
+    // Setup Stage, the setup packet is followed by the data for host to device requests
+    if !completion && stage == 0 && data.len() >= 8 {
+        let mut setup = [0; 8];
+        setup.copy_from_slice(&data[0..8]);
+        return Some(UrbEvent {
+            id: LittleEndian::read_u64(&packet[2..10]),
+            time,
+            submit: true,
+            setup: Some(setup),
+            length: LittleEndian::read_u16(&setup[6..8]) as usize,
+            data: data[8..].to_vec(),
+        });
+    }
+
+    // Complete Stage, carries the data for device to host requests
+    if completion && stage == 3 {
+        return Some(UrbEvent {
+            id: LittleEndian::read_u64(&packet[2..10]),
+            time,
+            submit: false,
+            setup: None,
+            length: LittleEndian::read_u32(&packet[23..27]) as usize,
+            data: data.to_vec(),
+        });
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    const MIC_LEVEL: u32 = 0x80c << 12;
+
+    // The 16 byte header the GoXLR puts in front of every request and response
+    fn packet(command_id: u32, index: u16, body: &[u8]) -> Vec<u8> {
+        let mut packet = vec![0; 16];
+        LittleEndian::write_u32(&mut packet[0..4], command_id);
+        LittleEndian::write_u16(&mut packet[6..8], index);
+        packet.extend_from_slice(body);
+        packet
+    }
+
+    fn hex_words(data: &[u8]) -> String {
+        data.chunks(4)
+            .map(|chunk| chunk.iter().map(|byte| format!("{byte:02x}")).collect())
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+
+    fn usbmon_capture() -> String {
+        let request = packet(MIC_LEVEL, 7, &[1, 2, 3, 4]);
+        let response = packet(0, 7, &[0xaa, 0xbb]);
+        format!(
+            "ffff0001 1000000 S Co:1:002:0 s 40 02 0000 0000 0014 {} = {}\n\
+             ffff0001 1000100 C Co:1:002:0 0 20 >\n\
+             ffff0002 1000200 S Ci:1:002:0 s c0 03 0000 0000 0400 1024 <\n\
+             ffff0002 1000300 C Ci:1:002:0 0 {} = {}\n",
+            request.len(),
+            hex_words(&request),
+            response.len(),
+            hex_words(&response),
+        )
+    }
+
+    #[test]
+    fn usbmon_text_pairs_request_and_response() {
+        let transfers = read_usbmon_text(Cursor::new(usbmon_capture())).unwrap();
+        assert_eq!(transfers.len(), 1);
+
+        let transfer = &transfers[0];
+        assert_eq!(transfer.command(), Some(Command::GetMicrophoneLevel));
+        assert_eq!(transfer.command_index, 7);
+        assert_eq!(transfer.request, vec![1, 2, 3, 4]);
+        assert_eq!(transfer.response, Some(vec![0xaa, 0xbb]));
+        assert!(!transfer.truncated);
+        assert!((transfer.time - 0.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn usbmon_text_rejects_truncated_lines() {
+        assert!(read_usbmon_text(Cursor::new("ffff0001 1000000 S\n")).is_err());
+        assert!(read_usbmon_text(Cursor::new("ffff0001 1000000 S Co:1:002:0 s 40 02\n")).is_err());
+        assert!(
+            read_usbmon_text(Cursor::new(
+                "ffff0001 1000000 S Co:1:002:0 s 40 02 0000 0000 0014\n"
+            ))
+            .is_err()
+        );
+        assert!(
+            read_usbmon_text(Cursor::new("ffff0001 1000000 C Ci:1:002:0 0 4 = 123\n")).is_err()
+        );
+    }
+
+    #[test]
+    fn usbmon_text_marks_short_data_as_truncated() {
+        let request = packet(MIC_LEVEL, 1, &[0; 16]);
+        let line = format!(
+            "ffff0001 1000000 S Co:1:002:0 s 40 02 0000 0000 0020 64 = {}\n",
+            hex_words(&request)
+        );
+        let transfers = read_usbmon_text(Cursor::new(line)).unwrap();
+        assert!(transfers[0].truncated);
+    }
+
+    fn pcap_header(link_type: u32) -> Vec<u8> {
+        let mut header = vec![0; 24];
+        LittleEndian::write_u32(&mut header[0..4], 0xa1b2c3d4);
+        LittleEndian::write_u16(&mut header[4..6], 2);
+        LittleEndian::write_u16(&mut header[6..8], 4);
+        LittleEndian::write_u32(&mut header[16..20], 65535);
+        LittleEndian::write_u32(&mut header[20..24], link_type);
+        header
+    }
+
+    fn pcap_record(packet: &[u8]) -> Vec<u8> {
+        let mut record = vec![0; 16];
+        LittleEndian::write_u32(&mut record[0..4], 10);
+        LittleEndian::write_u32(&mut record[8..12], packet.len() as u32);
+        LittleEndian::write_u32(&mut record[12..16], packet.len() as u32);
+        record.extend_from_slice(packet);
+        record
+    }
+
+    fn usbmon_packet(id: u64, submit: bool, setup: Option<[u8; 8]>, data: &[u8]) -> Vec<u8> {
+        let mut packet = vec![0; 48];
+        LittleEndian::write_u64(&mut packet[0..8], id);
+        packet[8] = if submit { b'S' } else { b'C' };
+        packet[9] = 2;
+        packet[14] = if setup.is_some() { 0 } else { b'-' };
+        LittleEndian::write_u32(&mut packet[32..36], data.len() as u32);
+        if let Some(setup) = setup {
+            packet[40..48].copy_from_slice(&setup);
+        }
+        packet.extend_from_slice(data);
+        packet
+    }
+
+    fn usbmon_pcap() -> Vec<u8> {
+        let write = [0x40, REQUEST_WRITE, 0, 0, 0, 0, 20, 0];
+        let read = [0xc0, REQUEST_READ, 0, 0, 0, 0, 0, 4];
+
+        let mut pcap = pcap_header(LINKTYPE_USB_LINUX);
+        let request = packet(MIC_LEVEL, 3, &[9, 9, 9, 9]);
+        pcap.extend(pcap_record(&usbmon_packet(1, true, Some(write), &request)));
+        pcap.extend(pcap_record(&usbmon_packet(2, true, Some(read), &[])));
+        let response = packet(0, 3, &[5, 6]);
+        pcap.extend(pcap_record(&usbmon_packet(2, false, None, &response)));
+        pcap
+    }
+
+    #[test]
+    fn pcap_usbmon_pairs_request_and_response() {
+        let transfers = read_pcap(Cursor::new(usbmon_pcap())).unwrap();
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].command_id, MIC_LEVEL);
+        assert_eq!(transfers[0].request, vec![9, 9, 9, 9]);
+        assert_eq!(transfers[0].response, Some(vec![5, 6]));
+    }
+
+    #[test]
+    fn pcap_rejects_truncated_input() {
+        let pcap = usbmon_pcap();
+
+        // Cut the global header, a record header, and a packet body
+        assert!(read_pcap(Cursor::new(&pcap[..10])).is_err());
+        assert!(read_pcap(Cursor::new(&pcap[..24 + 8])).is_err());
+        assert!(read_pcap(Cursor::new(&pcap[..pcap.len() - 3])).is_err());
+
+        // A record claiming to be bigger than the snap length
+        let mut oversized = pcap_header(LINKTYPE_USB_LINUX);
+        let mut record = pcap_record(&[]);
+        LittleEndian::write_u32(&mut record[8..12], u32::MAX);
+        oversized.extend(record);
+        assert!(read_pcap(Cursor::new(oversized)).is_err());
+    }
+
+    #[test]
+    fn pcap_rejects_other_formats() {
+        // pcapng Section Header Block
+        let pcapng = [
+            0x0a, 0x0d, 0x0d, 0x0a, 0x1c, 0, 0, 0, 0x4d, 0x3c, 0x2b, 0x1a, 1, 0, 0, 0, 0xff, 0xff,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        ];
+        assert!(read_pcap(Cursor::new(pcapng)).is_err());
+
+        // Ethernet, rather than USB
+        assert!(read_pcap(Cursor::new(pcap_header(1))).is_err());
+    }
+
+    #[test]
+    fn pcap_skips_short_usbmon_packets() {
+        let mut pcap = pcap_header(LINKTYPE_USB_LINUX_MMAPPED);
+        pcap.extend(pcap_record(&usbmon_packet(1, true, None, &[])));
+        assert!(read_pcap(Cursor::new(pcap)).unwrap().is_empty());
+    }
+
+    fn usbpcap_packet(id: u64, completion: bool, stage: u8, data: &[u8]) -> Vec<u8> {
+        let mut packet = vec![0; 28];
+        LittleEndian::write_u16(&mut packet[0..2], 28);
+        LittleEndian::write_u64(&mut packet[2..10], id);
+        packet[16] = completion as u8;
+        packet[22] = 2;
+        LittleEndian::write_u32(&mut packet[23..27], data.len() as u32);
+        packet[27] = stage;
+        packet.extend_from_slice(data);
+        packet
+    }
+
+    #[test]
+    fn pcap_usbpcap_pairs_request_and_response() {
+        let mut write = vec![0x40, REQUEST_WRITE, 0, 0, 0, 0, 20, 0];
+        write.extend(packet(MIC_LEVEL, 4, &[1, 1, 1, 1]));
+        let read = [0xc0, REQUEST_READ, 0, 0, 0, 0, 0, 4];
+
+        let mut pcap = pcap_header(LINKTYPE_USBPCAP);
+        pcap.extend(pcap_record(&usbpcap_packet(1, false, 0, &write)));
+        pcap.extend(pcap_record(&usbpcap_packet(2, false, 0, &read)));
+        pcap.extend(pcap_record(&usbpcap_packet(
+            2,
+            true,
+            3,
+            &packet(0, 4, &[7]),
+        )));
+
+        let transfers = read_pcap(Cursor::new(pcap)).unwrap();
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].command_index, 4);
+        assert_eq!(transfers[0].request, vec![1, 1, 1, 1]);
+        assert_eq!(transfers[0].response, Some(vec![7]));
+    }
+
+    #[test]
+    fn pcap_skips_malformed_usbpcap_packets() {
+        // A header length running past the end of the packet
+        let mut packet = usbpcap_packet(1, false, 0, &[]);
+        LittleEndian::write_u16(&mut packet[0..2], 100);
+
+        let mut pcap = pcap_header(LINKTYPE_USBPCAP);
+        pcap.extend(pcap_record(&packet));
+        pcap.extend(pcap_record(&[0; 12]));
+        assert!(read_pcap(Cursor::new(pcap)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn routing_decodes_both_layouts() {
+        let mut body = vec![0; 22];
+        body[OutputDevice::HeadphonesLeft.position(false)] = 0x20;
+        let routing = decode_routing(&body).unwrap();
+        assert_eq!(routing.len(), OutputDevice::iter().count() - 2);
+        assert!(
+            routing.iter().any(
+                |(output, value)| matches!(output, OutputDevice::HeadphonesLeft) && *value == 0x20
+            )
+        );
+
+        let routing = decode_routing(&[0; 26]).unwrap();
+        assert_eq!(routing.len(), OutputDevice::iter().count());
+
+        assert!(decode_routing(&[0; 21]).is_err());
+        assert!(decode_routing(&[]).is_err());
+    }
+
+    #[test]
+    fn colour_map_decodes_both_layouts() {
+        for length in [328, 520] {
+            let colours = decode_colour_map(&vec![0; length]).unwrap();
+            assert_eq!(colours.len(), ColourTargets::iter().count());
+        }
+        assert!(decode_colour_map(&[0; 327]).is_err());
+    }
+
+    #[test]
+    fn parameters_reject_bad_input() {
+        let key = EffectKey::iter().next().unwrap();
+        let mut body = vec![0; 8];
+        LittleEndian::write_u32(&mut body[0..4], key as u32);
+        LittleEndian::write_i32(&mut body[4..8], -5);
+        assert_eq!(decode_effect_parameters(&body).unwrap(), vec![(key, -5)]);
+
+        assert!(decode_effect_parameters(&body[..7]).is_err());
+        LittleEndian::write_u32(&mut body[0..4], u32::MAX);
+        assert!(decode_effect_parameters(&body).is_err());
+        assert!(decode_microphone_parameters(&body).is_err());
+        assert!(decode_microphone_parameters(&[0; 3]).is_err());
+    }
+}
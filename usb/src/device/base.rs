@@ -7,6 +7,7 @@ use crate::commands::{
     Command, FirmwareAction, FirmwareCommand, HardwareInfoCommand, SystemInfoCommand,
 };
 use crate::dcp::DCPCategory;
+use crate::device::emulated::Emulation;
 use crate::routing::InputDevice;
 use anyhow::{Result, bail};
 use byteorder::{ByteOrder, LittleEndian, ReadBytesExt, WriteBytesExt};
@@ -43,6 +44,17 @@ pub trait ExecutableGoXLR {
 
     fn perform_request(&mut self, command: Command, body: &[u8], retry: bool) -> Result<Vec<u8>>;
     fn get_descriptor(&self) -> Result<UsbData>;
+
+    // Sends any writes which are being held back to be coalesced, devices which write
+    // immediately have nothing to do here. `force` flushes regardless of how long they've waited.
+    fn flush_writes(&mut self, _force: bool) -> Result<()> {
+        Ok(())
+    }
+
+    // Whether flush_writes has anything to do, so callers only need to poll it when it does.
+    fn has_pending_writes(&self) -> bool {
+        false
+    }
 }
 
 // These are commands that can be executed, but perform_request must be implemented..
@@ -547,6 +559,7 @@ pub struct GoXLRDevice {
     pub(crate) bus_number: u8,
     pub(crate) address: u8,
     pub(crate) identifier: Option<String>,
+    pub(crate) emulated: Option<Emulation>,
 }
 
 impl GoXLRDevice {
@@ -560,6 +573,10 @@ impl GoXLRDevice {
     pub fn identifier(&self) -> &Option<String> {
         &self.identifier
     }
+
+    pub fn is_emulated(&self) -> bool {
+        self.emulated.is_some()
+    }
 }
 
 pub struct UsbData {
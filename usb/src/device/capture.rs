@@ -0,0 +1,516 @@
+// Capture and Replay of USB traffic. The capturing device wraps a real device and records every
+// request and response to a JSON Lines file, the replay device reads that file back and serves
+// the recorded responses in the order they were originally received. This allows a user's
+// exact device interaction to be reproduced locally without needing their hardware.
+use crate::commands::Command;
+use crate::device::base::{
+    AttachGoXLR, ExecutableGoXLR, FullGoXLRDevice, GoXLRCommands, GoXLRDevice, UsbData,
+};
+use crate::device::emulated::{Emulation, spawn_event_poller};
+use crate::{PID_GOXLR_FULL, VID_GOXLR};
+use anyhow::{Result, anyhow, bail};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+use tokio::sync::mpsc::Sender;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum CaptureRecord {
+    Descriptor {
+        vendor_id: u16,
+        product_id: u16,
+        device_version: (u8, u8, u8),
+        manufacturer: String,
+        product_name: String,
+    },
+    Request {
+        time_ms: u64,
+        command: String,
+        command_id: u32,
+        request: String,
+        response: Option<String>,
+        error: Option<String>,
+    },
+}
+
+/// Reads all the records from a capture file
+pub fn load_capture(path: &Path) -> Result<Vec<CaptureRecord>> {
+    let reader = BufReader::new(File::open(path)?);
+
+    let mut records = vec![];
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = serde_json::from_str(&line)
+            .map_err(|e| anyhow!("Invalid Capture Record on line {}: {}", index + 1, e))?;
+        records.push(record);
+    }
+    Ok(records)
+}
+
+pub fn to_hex(data: &[u8]) -> String {
+    data.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+pub fn from_hex(hex: &str) -> Result<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        bail!("Hex String has an odd length");
+    }
+
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).map_err(|e| anyhow!("Invalid Hex: {}", e)))
+        .collect()
+}
+
+pub struct CapturingGoXLR {
+    goxlr: Box<dyn FullGoXLRDevice>,
+    writer: BufWriter<File>,
+    started: Instant,
+}
+
+impl CapturingGoXLR {
+    /// Wraps an existing device, so that all requests made to it are written to the path
+    pub fn wrap(goxlr: Box<dyn FullGoXLRDevice>, path: &Path) -> Result<Box<dyn FullGoXLRDevice>> {
+        info!("Capturing USB Traffic to {}", path.to_string_lossy());
+
+        let descriptor = goxlr.get_descriptor()?;
+        let mut capture = Self {
+            goxlr,
+            writer: BufWriter::new(File::create(path)?),
+            started: Instant::now(),
+        };
+
+        capture.write_record(&CaptureRecord::Descriptor {
+            vendor_id: descriptor.vendor_id(),
+            product_id: descriptor.product_id(),
+            device_version: descriptor.device_version(),
+            manufacturer: descriptor.device_manufacturer(),
+            product_name: descriptor.product_name(),
+        })?;
+
+        Ok(Box::new(capture))
+    }
+
+    fn write_record(&mut self, record: &CaptureRecord) -> Result<()> {
+        serde_json::to_writer(&mut self.writer, record)?;
+        self.writer.write_all(b"\n")?;
+
+        // Flush on every record, captures are most useful when something has gone wrong.
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+impl AttachGoXLR for CapturingGoXLR {
+    fn from_device(
+        _device: GoXLRDevice,
+        _disconnect_sender: Sender<String>,
+        _event_sender: Sender<String>,
+        _skip_pause: bool,
+    ) -> Result<Box<dyn FullGoXLRDevice>> {
+        bail!("A Capturing device must be created by wrapping an existing device");
+    }
+
+    fn set_unique_identifier(&mut self, identifier: String) {
+        self.goxlr.set_unique_identifier(identifier);
+    }
+
+    fn is_connected(&mut self) -> bool {
+        self.goxlr.is_connected()
+    }
+
+    fn set_is_polling(&mut self, polling: bool) {
+        self.goxlr.set_is_polling(polling);
+    }
+}
+
+impl ExecutableGoXLR for CapturingGoXLR {
+    fn perform_request(&mut self, command: Command, body: &[u8], retry: bool) -> Result<Vec<u8>> {
+        let result = self.goxlr.perform_request(command, body, retry);
+
+        let (response, error) = match &result {
+            Ok(response) => (Some(to_hex(response)), None),
+            Err(error) => (None, Some(error.to_string())),
+        };
+
+        let record = CaptureRecord::Request {
+            time_ms: self.started.elapsed().as_millis() as u64,
+            command: format!("{command:?}"),
+            command_id: command.command_id(),
+            request: to_hex(body),
+            response,
+            error,
+        };
+
+        // A failed write shouldn't prevent the device from working..
+        if let Err(error) = self.write_record(&record) {
+            warn!("Unable to write USB Capture: {}", error);
+        }
+        result
+    }
+
+    fn get_descriptor(&self) -> Result<UsbData> {
+        self.goxlr.get_descriptor()
+    }
+
+    fn flush_writes(&mut self, force: bool) -> Result<()> {
+        self.goxlr.flush_writes(force)
+    }
+
+    fn has_pending_writes(&self) -> bool {
+        self.goxlr.has_pending_writes()
+    }
+}
+
+impl GoXLRCommands for CapturingGoXLR {}
+impl FullGoXLRDevice for CapturingGoXLR {}
+
+#[derive(Debug, Clone)]
+struct ReplayEntry {
+    command: String,
+    command_id: u32,
+    request: Vec<u8>,
+    response: Result<Vec<u8>, String>,
+}
+
+pub struct ReplayGoXLR {
+    descriptor: (u16, u16, (u8, u8, u8), String, String),
+
+    // Responses in the order they were captured, requests need to arrive in the same order.
+    responses: VecDeque<ReplayEntry>,
+
+    // Polled commands run on timers, so how often they appear depends on timing rather than on
+    // what the daemon is doing. These are answered with the most recent captured response.
+    polled: HashMap<u32, ReplayEntry>,
+
+    event_sender: Sender<String>,
+    stop_polling: Arc<AtomicBool>,
+    stopping: Arc<AtomicBool>,
+}
+
+impl AttachGoXLR for ReplayGoXLR {
+    fn from_device(
+        device: GoXLRDevice,
+        _disconnect_sender: Sender<String>,
+        event_sender: Sender<String>,
+        _skip_pause: bool,
+    ) -> Result<Box<dyn FullGoXLRDevice>> {
+        let Some(Emulation::Replay(path)) = &device.emulated else {
+            bail!("Device is not a Replay GoXLR");
+        };
+
+        info!("Replaying USB Capture from {}", path.to_string_lossy());
+
+        let mut descriptor = (
+            VID_GOXLR,
+            PID_GOXLR_FULL,
+            (2, 0, 0),
+            String::from("TC-Helicon"),
+            String::from("GoXLR"),
+        );
+        let mut responses = VecDeque::new();
+
+        for record in load_capture(path)? {
+            match record {
+                CaptureRecord::Descriptor {
+                    vendor_id,
+                    product_id,
+                    device_version,
+                    manufacturer,
+                    product_name,
+                } => {
+                    let product_name = format!("{product_name} (Replay)");
+                    descriptor = (
+                        vendor_id,
+                        product_id,
+                        device_version,
+                        manufacturer,
+                        product_name,
+                    );
+                }
+                CaptureRecord::Request {
+                    command,
+                    command_id,
+                    request,
+                    response,
+                    error,
+                    ..
+                } => {
+                    let response = match response {
+                        Some(response) => Ok(from_hex(&response)?),
+                        None => Err(error.unwrap_or_else(|| String::from("Unknown Error"))),
+                    };
+
+                    responses.push_back(ReplayEntry {
+                        command,
+                        command_id,
+                        request: from_hex(&request)?,
+                        response,
+                    });
+                }
+            }
+        }
+
+        Ok(Box::new(Self {
+            descriptor,
+            responses,
+            polled: HashMap::new(),
+            event_sender,
+            stop_polling: Arc::new(AtomicBool::new(false)),
+            stopping: Arc::new(AtomicBool::new(false)),
+        }))
+    }
+
+    fn set_unique_identifier(&mut self, identifier: String) {
+        spawn_event_poller(
+            identifier,
+            self.event_sender.clone(),
+            self.stopping.clone(),
+            self.stop_polling.clone(),
+        );
+    }
+
+    fn is_connected(&mut self) -> bool {
+        true
+    }
+
+    fn set_is_polling(&mut self, polling: bool) {
+        self.stop_polling.store(!polling, Ordering::Relaxed);
+    }
+}
+
+impl ExecutableGoXLR for ReplayGoXLR {
+    fn perform_request(&mut self, command: Command, body: &[u8], _retry: bool) -> Result<Vec<u8>> {
+        let command_id = command.command_id();
+
+        // Any captured polls before the next real request only update what polls are answered
+        // with, so the final polled state holds once the capture has run out.
+        while self
+            .responses
+            .front()
+            .is_some_and(|entry| is_polled(entry.command_id))
+        {
+            if let Some(entry) = self.responses.pop_front() {
+                self.polled.insert(entry.command_id, entry);
+            }
+        }
+
+        if is_polled(command_id) {
+            let Some(entry) = self.polled.get(&command_id) else {
+                debug!("No Captured Response for {:?}, returning empty", command);
+                return Ok(vec![]);
+            };
+            return entry.response.clone().map_err(|error| anyhow!(error));
+        }
+
+        let Some(entry) = self.responses.pop_front() else {
+            bail!("Replay Finished, no Captured Response for {:?}", command);
+        };
+
+        // If we've gone off script, the rest of the capture can't be trusted.
+        if entry.command_id != command_id || entry.request != body {
+            debug!("Captured: {}", to_hex(&entry.request));
+            debug!("Received: {}", to_hex(body));
+            let expected = entry.command.clone();
+            self.responses.push_front(entry);
+            bail!(
+                "Replay Diverged, expected {} but received {:?}",
+                expected,
+                command
+            );
+        }
+        entry.response.map_err(|error| anyhow!(error))
+    }
+
+    fn get_descriptor(&self) -> Result<UsbData> {
+        let (vendor_id, product_id, device_version, manufacturer, product_name) =
+            self.descriptor.clone();
+
+        Ok(UsbData {
+            vendor_id,
+            product_id,
+            device_version,
+            device_manufacturer: manufacturer,
+            product_name,
+        })
+    }
+}
+
+fn is_polled(command_id: u32) -> bool {
+    command_id == Command::GetButtonStates.command_id()
+        || command_id == Command::GetMicrophoneLevel.command_id()
+}
+
+impl Drop for ReplayGoXLR {
+    fn drop(&mut self) {
+        self.stopping.store(true, Ordering::Relaxed);
+    }
+}
+
+impl GoXLRCommands for ReplayGoXLR {}
+impl FullGoXLRDevice for ReplayGoXLR {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::emulated::create_emulated_device;
+    use goxlr_types::{ChannelName, DeviceType, FaderName};
+    use std::path::PathBuf;
+    use tokio::sync::mpsc;
+
+    // Removes the capture file once the test is done with it
+    struct CaptureFile(PathBuf);
+
+    impl CaptureFile {
+        fn new(name: &str) -> Self {
+            let file = format!("goxlr-capture-{}-{name}.jsonl", std::process::id());
+            Self(std::env::temp_dir().join(file))
+        }
+
+        fn write(&self, records: &[CaptureRecord]) -> Result<()> {
+            let mut writer = BufWriter::new(File::create(&self.0)?);
+            for record in records {
+                serde_json::to_writer(&mut writer, record)?;
+                writer.write_all(b"\n")?;
+            }
+            writer.flush()?;
+            Ok(())
+        }
+    }
+
+    impl Drop for CaptureFile {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+
+    fn attach(emulation: Emulation) -> Result<Box<dyn FullGoXLRDevice>> {
+        let (disconnect_sender, _) = mpsc::channel(1);
+        let (event_sender, _) = mpsc::channel(1);
+        let device = create_emulated_device(emulation, 0);
+        crate::device::emulated::from_device(device, disconnect_sender, event_sender, false)
+    }
+
+    fn request(command: Command, request: &[u8], response: &[u8]) -> CaptureRecord {
+        CaptureRecord::Request {
+            time_ms: 0,
+            command: format!("{command:?}"),
+            command_id: command.command_id(),
+            request: to_hex(request),
+            response: Some(to_hex(response)),
+            error: None,
+        }
+    }
+
+    fn replay(file: &CaptureFile) -> Result<Box<dyn FullGoXLRDevice>> {
+        attach(Emulation::Replay(file.0.clone()))
+    }
+
+    const MIC_VOLUME: Command = Command::SetChannelVolume(ChannelName::Mic);
+
+    #[test]
+    fn hex_round_trip() -> Result<()> {
+        assert_eq!(to_hex(&[0x00, 0x0f, 0xa0, 0xff]), "000fa0ff");
+        assert_eq!(from_hex("000fa0ff")?, [0x00, 0x0f, 0xa0, 0xff]);
+        assert!(from_hex("abc").is_err());
+        assert!(from_hex("zz").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn replays_a_capture() -> Result<()> {
+        let file = CaptureFile::new("round-trip");
+
+        let mut capture =
+            CapturingGoXLR::wrap(attach(Emulation::Device(DeviceType::Full))?, &file.0)?;
+        capture.set_fader(FaderName::A, ChannelName::Game)?;
+        capture.set_volume(ChannelName::Game, 200)?;
+        let serial = capture.get_serial_number()?;
+        let buttons = capture.get_button_states()?;
+        assert!(capture.perform_request(MIC_VOLUME, &[], false).is_err());
+        drop(capture);
+
+        // A descriptor, then one record per request including the failed one
+        let records = load_capture(&file.0)?;
+        assert_eq!(records.len(), 6);
+        assert!(matches!(records[0], CaptureRecord::Descriptor { .. }));
+        assert!(matches!(
+            &records[5],
+            CaptureRecord::Request { error: Some(_), .. }
+        ));
+
+        let mut replay = replay(&file)?;
+        assert_eq!(
+            replay.get_descriptor()?.product_name(),
+            "GoXLR (Emulated) (Replay)"
+        );
+        replay.set_fader(FaderName::A, ChannelName::Game)?;
+        replay.set_volume(ChannelName::Game, 200)?;
+        assert_eq!(replay.get_serial_number()?, serial);
+        assert_eq!(replay.get_button_states()?.volumes, buttons.volumes);
+        assert!(replay.perform_request(MIC_VOLUME, &[], false).is_err());
+
+        let error = replay.set_volume(ChannelName::Game, 200).unwrap_err();
+        assert!(error.to_string().starts_with("Replay Finished"));
+        Ok(())
+    }
+
+    #[test]
+    fn answers_polls_from_the_latest_capture() -> Result<()> {
+        let file = CaptureFile::new("polled");
+        file.write(&[
+            request(Command::GetMicrophoneLevel, &[], &[0x01, 0x00]),
+            request(MIC_VOLUME, &[0x10], &[]),
+            request(Command::GetMicrophoneLevel, &[], &[0x02, 0x00]),
+        ])?;
+        let mut replay = replay(&file)?;
+
+        // Nothing was captured for this, so it's answered with nothing
+        assert!(
+            replay
+                .request_data(Command::GetButtonStates, &[])?
+                .is_empty()
+        );
+
+        // Polls can come more or less often than they were captured..
+        assert_eq!(replay.get_microphone_level()?, 1);
+        assert_eq!(replay.get_microphone_level()?, 1);
+
+        // ..and once the capture runs out, the last one is kept
+        replay.set_volume(ChannelName::Mic, 0x10)?;
+        assert_eq!(replay.get_microphone_level()?, 2);
+        assert_eq!(replay.get_microphone_level()?, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn bails_on_divergence() -> Result<()> {
+        let file = CaptureFile::new("diverged");
+        file.write(&[
+            request(MIC_VOLUME, &[0x10], &[]),
+            request(Command::SetChannelVolume(ChannelName::Game), &[0x20], &[]),
+        ])?;
+        let mut replay = replay(&file)?;
+
+        // A different body, or a different command, both go off script
+        let error = replay.set_volume(ChannelName::Mic, 0x11).unwrap_err();
+        assert!(error.to_string().starts_with("Replay Diverged"));
+        let error = replay.set_volume(ChannelName::Game, 0x20).unwrap_err();
+        assert!(error.to_string().starts_with("Replay Diverged"));
+
+        // The expected request is kept, so the replay can carry on if it gets back on track
+        replay.set_volume(ChannelName::Mic, 0x10)?;
+        replay.set_volume(ChannelName::Game, 0x20)?;
+        Ok(())
+    }
+}
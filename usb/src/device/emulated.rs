@@ -0,0 +1,599 @@
+// This is a software-only GoXLR, it keeps an in-memory model of the device and responds to
+// requests in the same way the hardware does. It allows the daemon to be run (and profiles,
+// scripts and UIs to be developed) on machines which don't have a GoXLR attached.
+use crate::buttonstate::Buttons;
+use crate::channelstate::ChannelState;
+use crate::commands::{Command, HardwareInfoCommand, SystemInfoCommand};
+use crate::device::base::{
+    AttachGoXLR, ExecutableGoXLR, FullGoXLRDevice, GoXLRCommands, GoXLRDevice, UsbData,
+};
+use crate::device::capture::ReplayGoXLR;
+use crate::{PID_GOXLR_FULL, PID_GOXLR_MINI, VID_GOXLR};
+use anyhow::{Result, anyhow, bail};
+use byteorder::{ByteOrder, LittleEndian};
+use enum_map::EnumMap;
+use enumset::EnumSet;
+use goxlr_types::{
+    ChannelName, DeviceType, EncoderName, FaderName, SubMixChannelName, VersionNumber,
+};
+use log::{debug, warn};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use strum::IntoEnumIterator;
+use tokio::sync::mpsc::Sender;
+use tokio::task;
+
+#[derive(Debug, Clone)]
+pub enum Emulation {
+    /// A modelled device of the specified type
+    Device(DeviceType),
+
+    /// A device which replays the responses from a USB capture file
+    Replay(PathBuf),
+}
+
+/// Creates a handle for an emulated device, this can be passed to `from_device` in the same way
+/// as a device returned from `find_devices`. The index should be unique per emulated device.
+pub fn create_emulated_device(emulation: Emulation, index: u8) -> GoXLRDevice {
+    GoXLRDevice {
+        bus_number: 0,
+        address: index,
+        identifier: Some(format!("emulated-{index}")),
+        emulated: Some(emulation),
+    }
+}
+
+pub(crate) fn from_device(
+    device: GoXLRDevice,
+    disconnect_sender: Sender<String>,
+    event_sender: Sender<String>,
+    skip_pause: bool,
+) -> Result<Box<dyn FullGoXLRDevice>> {
+    match device.emulated {
+        Some(Emulation::Device(_)) => {
+            EmulatedGoXLR::from_device(device, disconnect_sender, event_sender, skip_pause)
+        }
+        Some(Emulation::Replay(_)) => {
+            ReplayGoXLR::from_device(device, disconnect_sender, event_sender, skip_pause)
+        }
+        None => bail!("Device is not an Emulated GoXLR"),
+    }
+}
+
+// Emulated devices don't have any real inputs to poll, but the daemon still expects to be
+// periodically told to check for them, so we'll do the same as the hardware backends.
+pub(crate) fn spawn_event_poller(
+    event_id: String,
+    sender: Sender<String>,
+    stopping: Arc<AtomicBool>,
+    stopped: Arc<AtomicBool>,
+) {
+    let poll_millis = 20;
+    task::spawn(async move {
+        loop {
+            if stopping.load(Ordering::Relaxed) {
+                break;
+            }
+
+            if !stopped.load(Ordering::Relaxed) && sender.capacity() > 0 {
+                if sender.is_closed() {
+                    warn!("Sender Closed for {}", event_id);
+                    break;
+                }
+                let _ = sender.send(event_id.clone()).await;
+            }
+
+            tokio::time::sleep(Duration::from_millis(poll_millis)).await;
+        }
+    });
+}
+
+pub struct EmulatedGoXLR {
+    device_type: DeviceType,
+    serial_number: String,
+    state: EmulatedState,
+
+    event_sender: Sender<String>,
+    identifier: Option<String>,
+
+    stop_polling: Arc<AtomicBool>,
+    stopping: Arc<AtomicBool>,
+}
+
+// The in-memory representation of the device, updated as commands are received.
+#[derive(Debug, Clone)]
+pub struct EmulatedState {
+    pub firmware: VersionNumber,
+    pub dice: VersionNumber,
+
+    pub faders: EnumMap<FaderName, ChannelName>,
+    pub fader_positions: EnumMap<FaderName, u8>,
+    pub fader_display: EnumMap<FaderName, [u8; 2]>,
+    pub scribbles: EnumMap<FaderName, Vec<u8>>,
+
+    pub volumes: EnumMap<ChannelName, u8>,
+    pub channel_states: EnumMap<ChannelName, ChannelState>,
+    pub sub_volumes: EnumMap<SubMixChannelName, u8>,
+    pub channel_mixes: Vec<u8>,
+    pub monitored_mix: u8,
+
+    pub encoders: EnumMap<EncoderName, i8>,
+    pub encoder_modes: EnumMap<EncoderName, [u8; 2]>,
+
+    // Keyed by the routing::InputDevice id
+    pub routing: BTreeMap<u8, Vec<u8>>,
+
+    pub colour_map: Vec<u8>,
+    pub button_states: [u8; 24],
+    pub pressed: EnumSet<Buttons>,
+    pub animation: [u8; 5],
+
+    // Keyed by the EffectKey / MicrophoneParamKey values
+    pub effects: BTreeMap<u32, i32>,
+    pub mic_params: BTreeMap<u32, [u8; 4]>,
+    pub microphone_level: u16,
+}
+
+impl EmulatedState {
+    fn new(device_type: DeviceType) -> Self {
+        // Report firmware new enough to enable every feature the daemon supports.
+        let firmware = match device_type {
+            DeviceType::Mini => VersionNumber(1, 3, Some(0), Some(107)),
+            _ => VersionNumber(1, 5, Some(0), Some(164)),
+        };
+
+        Self {
+            firmware,
+            dice: VersionNumber(1, 0, Some(20), Some(1)),
+
+            faders: EnumMap::from_array([
+                ChannelName::Mic,
+                ChannelName::Chat,
+                ChannelName::Music,
+                ChannelName::System,
+            ]),
+            fader_positions: EnumMap::default(),
+            fader_display: EnumMap::default(),
+            scribbles: EnumMap::from_fn(|_| vec![0; 1024]),
+
+            volumes: EnumMap::default(),
+            channel_states: EnumMap::default(),
+            sub_volumes: EnumMap::default(),
+            channel_mixes: vec![],
+            monitored_mix: 0,
+
+            encoders: EnumMap::default(),
+            encoder_modes: EnumMap::default(),
+
+            routing: BTreeMap::new(),
+
+            colour_map: vec![0; 520],
+            button_states: [0; 24],
+            pressed: EnumSet::empty(),
+            animation: [0; 5],
+
+            effects: BTreeMap::new(),
+            mic_params: BTreeMap::new(),
+            microphone_level: 0,
+        }
+    }
+}
+
+impl EmulatedGoXLR {
+    pub fn state(&self) -> &EmulatedState {
+        &self.state
+    }
+
+    pub fn state_mut(&mut self) -> &mut EmulatedState {
+        &mut self.state
+    }
+
+    fn product_id(&self) -> u16 {
+        match self.device_type {
+            DeviceType::Mini => PID_GOXLR_MINI,
+            _ => PID_GOXLR_FULL,
+        }
+    }
+
+    fn get_firmware_packet(&self) -> Vec<u8> {
+        let firmware = &self.state.firmware;
+        let dice = &self.state.dice;
+
+        let firmware_packed =
+            (firmware.0 << 12) | ((firmware.1 & 0xF) << 8) | (firmware.2.unwrap_or(0) & 0xFF);
+        let dice_packed =
+            ((dice.0 & 0xF) << 20) | ((dice.1 & 0xFF) << 12) | (dice.2.unwrap_or(0) & 0xFFF);
+
+        let mut packet = vec![0; 24];
+        LittleEndian::write_u32(&mut packet[0..4], firmware_packed);
+        LittleEndian::write_u32(&mut packet[4..8], firmware.3.unwrap_or(0));
+        LittleEndian::write_u32(&mut packet[8..12], 0);
+        LittleEndian::write_u32(&mut packet[12..16], 1);
+        LittleEndian::write_u32(&mut packet[16..20], dice.3.unwrap_or(0));
+        LittleEndian::write_u32(&mut packet[20..24], dice_packed);
+        packet
+    }
+
+    fn get_serial_packet(&self) -> Vec<u8> {
+        let mut packet = vec![0; 40];
+
+        let serial = self.serial_number.as_bytes();
+        let serial_len = serial.len().min(23);
+        packet[..serial_len].copy_from_slice(&serial[..serial_len]);
+
+        let date = b"2024-01-01";
+        packet[24..24 + date.len()].copy_from_slice(date);
+        packet
+    }
+
+    fn get_button_state_packet(&self) -> Vec<u8> {
+        let mut packet = vec![0; 12];
+
+        let mut pressed = 0;
+        for button in self.state.pressed {
+            pressed |= 1 << button as u8;
+        }
+        LittleEndian::write_u32(&mut packet[0..4], pressed);
+
+        for encoder in EncoderName::iter() {
+            packet[4 + encoder as usize] = self.state.encoders[encoder] as u8;
+        }
+
+        // The full device has motorised faders which follow the volume of the assigned channel,
+        // where as the mini reports the physical fader position.
+        for fader in FaderName::iter() {
+            packet[8 + fader as usize] = match self.device_type {
+                DeviceType::Mini => self.state.fader_positions[fader],
+                _ => self.state.volumes[self.state.faders[fader]],
+            };
+        }
+        packet
+    }
+}
+
+impl AttachGoXLR for EmulatedGoXLR {
+    fn from_device(
+        device: GoXLRDevice,
+        _disconnect_sender: Sender<String>,
+        event_sender: Sender<String>,
+        _skip_pause: bool,
+    ) -> Result<Box<dyn FullGoXLRDevice>> {
+        let Some(Emulation::Device(device_type)) = device.emulated else {
+            bail!("Device is not an Emulated GoXLR");
+        };
+
+        let serial_number = match device_type {
+            DeviceType::Mini => format!("EMULATEDMINI{:04}", device.address),
+            _ => format!("EMULATEDFULL{:04}", device.address),
+        };
+
+        debug!("Creating Emulated GoXLR {}", serial_number);
+        Ok(Box::new(Self {
+            device_type,
+            serial_number,
+            state: EmulatedState::new(device_type),
+            event_sender,
+            identifier: None,
+            stop_polling: Arc::new(AtomicBool::new(false)),
+            stopping: Arc::new(AtomicBool::new(false)),
+        }))
+    }
+
+    fn set_unique_identifier(&mut self, identifier: String) {
+        self.identifier = Some(identifier.clone());
+        spawn_event_poller(
+            identifier,
+            self.event_sender.clone(),
+            self.stopping.clone(),
+            self.stop_polling.clone(),
+        );
+    }
+
+    fn is_connected(&mut self) -> bool {
+        true
+    }
+
+    fn set_is_polling(&mut self, polling: bool) {
+        self.stop_polling.store(!polling, Ordering::Relaxed);
+    }
+}
+
+impl ExecutableGoXLR for EmulatedGoXLR {
+    fn perform_request(&mut self, command: Command, body: &[u8], _retry: bool) -> Result<Vec<u8>> {
+        let state = &mut self.state;
+
+        let response = match command {
+            Command::ResetCommandIndex => vec![],
+            Command::SystemInfo(SystemInfoCommand::FirmwareVersion) => vec![0; 16],
+            Command::SystemInfo(SystemInfoCommand::SupportsDCPCategory) => {
+                let mut out = vec![0; 2];
+                LittleEndian::write_u16(&mut out, 1);
+                out
+            }
+            Command::GetHardwareInfo(HardwareInfoCommand::FirmwareVersion) => {
+                self.get_firmware_packet()
+            }
+            Command::GetHardwareInfo(HardwareInfoCommand::SerialNumber) => self.get_serial_packet(),
+            Command::GetButtonStates => self.get_button_state_packet(),
+            Command::GetMicrophoneLevel => {
+                let mut out = vec![0; 2];
+                LittleEndian::write_u16(&mut out, state.microphone_level);
+                out
+            }
+            Command::SetChannelState(channel) => {
+                check_length(command, body, 1)?;
+                state.channel_states[channel] = match body[0] {
+                    0x01 => ChannelState::Muted,
+                    _ => ChannelState::Unmuted,
+                };
+                vec![]
+            }
+            Command::SetChannelVolume(channel) => {
+                check_length(command, body, 1)?;
+                state.volumes[channel] = body[0];
+                vec![]
+            }
+            Command::SetSubChannelVolume(channel) => {
+                check_length(command, body, 1)?;
+                state.sub_volumes[channel] = body[0];
+                vec![]
+            }
+            Command::SetEncoderValue(encoder) => {
+                check_length(command, body, 1)?;
+                state.encoders[encoder] = body[0] as i8;
+                vec![]
+            }
+            Command::SetEncoderMode(encoder) => {
+                check_length(command, body, 2)?;
+                state.encoder_modes[encoder] = [body[0], body[1]];
+                vec![]
+            }
+            Command::SetFader(fader) => {
+                check_length(command, body, 1)?;
+                let channel = ChannelName::iter()
+                    .find(|channel| *channel as u8 == body[0])
+                    .ok_or_else(|| anyhow!("Invalid Channel for {:?}: {}", command, body[0]))?;
+                state.faders[fader] = channel;
+                vec![]
+            }
+            Command::SetFaderDisplayMode(fader) => {
+                check_length(command, body, 2)?;
+                state.fader_display[fader] = [body[0], body[1]];
+                vec![]
+            }
+            Command::SetScribble(fader) => {
+                check_length(command, body, 1024)?;
+                state.scribbles[fader] = body.to_vec();
+                vec![]
+            }
+            Command::SetRouting(input_device) => {
+                state.routing.insert(input_device.id(), body.to_vec());
+                vec![]
+            }
+            Command::SetButtonStates() => {
+                check_length(command, body, 24)?;
+                state.button_states.copy_from_slice(&body[..24]);
+                vec![]
+            }
+            Command::SetColourMap() => {
+                if body.len() != 328 && body.len() != 520 {
+                    bail!("Invalid Colour Map Length: {}", body.len());
+                }
+                state.colour_map = body.to_vec();
+                vec![]
+            }
+            Command::SetAnimationMode => {
+                check_length(command, body, 5)?;
+                state.animation.copy_from_slice(&body[..5]);
+                vec![]
+            }
+            Command::SetEffectParameters => {
+                for chunk in body.chunks_exact(8) {
+                    let key = LittleEndian::read_u32(&chunk[0..4]);
+                    let value = LittleEndian::read_i32(&chunk[4..8]);
+                    state.effects.insert(key, value);
+                }
+                vec![]
+            }
+            Command::SetMicrophoneParameters => {
+                for chunk in body.chunks_exact(8) {
+                    let key = LittleEndian::read_u32(&chunk[0..4]);
+                    let mut value = [0; 4];
+                    value.copy_from_slice(&chunk[4..8]);
+                    state.mic_params.insert(key, value);
+                }
+                vec![]
+            }
+            Command::SetChannelMixes => {
+                state.channel_mixes = body.to_vec();
+                vec![]
+            }
+            Command::SetMonitoredMix => {
+                check_length(command, body, 1)?;
+                state.monitored_mix = body[0];
+                vec![]
+            }
+            Command::ExecuteFirmwareUpdateCommand(_) | Command::ExecuteFirmwareUpdateAction(_) => {
+                bail!("Firmware Updates are not supported on Emulated Devices");
+            }
+        };
+
+        Ok(response)
+    }
+
+    fn get_descriptor(&self) -> Result<UsbData> {
+        let product_name = match self.device_type {
+            DeviceType::Mini => "GoXLR Mini (Emulated)",
+            _ => "GoXLR (Emulated)",
+        };
+
+        Ok(UsbData {
+            vendor_id: VID_GOXLR,
+            product_id: self.product_id(),
+            device_version: (2, 0, 0),
+            device_manufacturer: String::from("TC-Helicon"),
+            product_name: String::from(product_name),
+        })
+    }
+}
+
+impl Drop for EmulatedGoXLR {
+    fn drop(&mut self) {
+        // Make sure the polling task is stopped along with the device.
+        self.stopping.store(true, Ordering::Relaxed);
+    }
+}
+
+impl GoXLRCommands for EmulatedGoXLR {}
+impl FullGoXLRDevice for EmulatedGoXLR {}
+
+fn check_length(command: Command, body: &[u8], length: usize) -> Result<()> {
+    if body.len() < length {
+        bail!(
+            "Malformed request for {:?}, Expected: {}, Received: {}",
+            command,
+            length,
+            body.len()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buttonstate::ButtonStates;
+    use goxlr_types::EffectKey;
+    use tokio::sync::mpsc;
+
+    fn emulated(device_type: DeviceType) -> EmulatedGoXLR {
+        let (event_sender, _) = mpsc::channel(1);
+        EmulatedGoXLR {
+            device_type,
+            serial_number: String::from("EMULATEDTEST0001"),
+            state: EmulatedState::new(device_type),
+            event_sender,
+            identifier: None,
+            stop_polling: Arc::new(AtomicBool::new(false)),
+            stopping: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    fn attach(emulation: Emulation, index: u8) -> Result<Box<dyn FullGoXLRDevice>> {
+        let (disconnect_sender, _) = mpsc::channel(1);
+        let (event_sender, _) = mpsc::channel(1);
+        let device = create_emulated_device(emulation, index);
+        from_device(device, disconnect_sender, event_sender, false)
+    }
+
+    #[test]
+    fn creates_emulated_devices() {
+        let device = create_emulated_device(Emulation::Device(DeviceType::Full), 3);
+        assert!(device.is_emulated());
+        assert_eq!(device.bus_number(), 0);
+        assert_eq!(device.address(), 3);
+        assert_eq!(device.identifier().as_deref(), Some("emulated-3"));
+    }
+
+    #[test]
+    fn reports_its_identity() -> Result<()> {
+        let mut device = attach(Emulation::Device(DeviceType::Full), 3)?;
+        let (serial, date) = device.get_serial_number()?;
+        assert_eq!(serial, "EMULATEDFULL0003");
+        assert_eq!(date, "2024-01-01");
+
+        let versions = device.get_firmware_version()?;
+        assert!(versions.firmware == VersionNumber(1, 5, Some(0), Some(164)));
+        assert!(versions.dice == VersionNumber(1, 0, Some(20), Some(1)));
+        assert_eq!(device.get_descriptor()?.product_id(), PID_GOXLR_FULL);
+        assert!(device.supports_dcp_category(crate::dcp::DCPCategory::Mixer)?);
+
+        let mut mini = attach(Emulation::Device(DeviceType::Mini), 1)?;
+        assert_eq!(mini.get_serial_number()?.0, "EMULATEDMINI0001");
+        assert_eq!(mini.get_descriptor()?.product_id(), PID_GOXLR_MINI);
+        Ok(())
+    }
+
+    #[test]
+    fn faders_follow_volumes_on_the_full_device() -> Result<()> {
+        let mut device = emulated(DeviceType::Full);
+        device.set_fader(FaderName::A, ChannelName::Game)?;
+        device.set_volume(ChannelName::Game, 200)?;
+        device.set_volume(ChannelName::Chat, 50)?;
+
+        assert_eq!(device.state().faders[FaderName::A], ChannelName::Game);
+        assert_eq!(device.state().volumes[ChannelName::Game], 200);
+        assert_eq!(device.get_button_states()?.volumes, [200, 50, 0, 0]);
+        Ok(())
+    }
+
+    #[test]
+    fn mini_reports_fader_positions() -> Result<()> {
+        let mut device = emulated(DeviceType::Mini);
+        device.set_volume(ChannelName::Mic, 200)?;
+        device.state_mut().fader_positions[FaderName::A] = 80;
+
+        assert_eq!(device.get_button_states()?.volumes[0], 80);
+        Ok(())
+    }
+
+    #[test]
+    fn reports_buttons_and_encoders() -> Result<()> {
+        let mut device = emulated(DeviceType::Full);
+        device.state_mut().pressed.insert(Buttons::Fader1Mute);
+        device.state_mut().pressed.insert(Buttons::Bleep);
+        device.set_encoder_value(EncoderName::Reverb, -5)?;
+
+        let states = device.get_button_states()?;
+        assert_eq!(states.pressed, Buttons::Fader1Mute | Buttons::Bleep);
+        assert_eq!(states.encoders, [0, 0, -5, 0]);
+        Ok(())
+    }
+
+    #[test]
+    fn stores_writes() -> Result<()> {
+        let mut device = emulated(DeviceType::Full);
+        device.set_channel_state(ChannelName::Music, ChannelState::Muted)?;
+        device.set_sub_volume(SubMixChannelName::Music, 40)?;
+        device.set_button_states([ButtonStates::Flashing; 24])?;
+        device.set_effect_values(&[(EffectKey::BleepLevel, -20)])?;
+
+        let state = device.state();
+        assert_eq!(
+            state.channel_states[ChannelName::Music],
+            ChannelState::Muted
+        );
+        assert_eq!(state.sub_volumes[SubMixChannelName::Music], 40);
+        assert_eq!(state.button_states, [ButtonStates::Flashing as u8; 24]);
+        assert_eq!(
+            state.effects.get(&(EffectKey::BleepLevel as u32)),
+            Some(&-20)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn rejects_malformed_requests() {
+        let mut device = emulated(DeviceType::Full);
+        let volume = Command::SetChannelVolume(ChannelName::Mic);
+        assert!(device.perform_request(volume, &[], false).is_err());
+        assert!(
+            device
+                .perform_request(Command::SetColourMap(), &[0; 100], false)
+                .is_err()
+        );
+        assert!(device.begin_firmware_upload().is_err());
+    }
+
+    #[test]
+    fn only_attaches_modelled_devices() {
+        let (disconnect_sender, _) = mpsc::channel(1);
+        let (event_sender, _) = mpsc::channel(1);
+        let device = create_emulated_device(Emulation::Replay(PathBuf::from("capture")), 0);
+        let attached = EmulatedGoXLR::from_device(device, disconnect_sender, event_sender, false);
+        assert!(attached.is_err());
+    }
+}
@@ -471,6 +471,7 @@ pub fn find_devices() -> Vec<GoXLRDevice> {
                         bus_number,
                         address,
                         identifier: None,
+                        emulated: None,
                     });
                 }
             }
@@ -6,6 +6,9 @@ use goxlr_types::{DriverInterface, VersionNumber};
 use tokio::sync::mpsc::Sender;
 
 pub mod base;
+pub mod capture;
+pub mod emulated;
+pub mod scheduler;
 
 cfg_if::cfg_if! {
     if #[cfg(target_os = "windows")] {
@@ -27,6 +30,9 @@ cfg_if::cfg_if! {
             event_sender: Sender<String>,
             skip_pause: bool,
         ) -> Result<Box<dyn FullGoXLRDevice>> {
+            if device.is_emulated() {
+                return emulated::from_device(device, disconnect_sender, event_sender, skip_pause);
+            }
             device::TUSBAudioGoXLR::from_device(device, disconnect_sender, event_sender, skip_pause)
         }
     } else {
@@ -48,6 +54,9 @@ cfg_if::cfg_if! {
             event_sender: Sender<String>,
             skip_pause: bool,
         ) -> Result<Box<dyn FullGoXLRDevice>> {
+            if device.is_emulated() {
+                return emulated::from_device(device, disconnect_sender, event_sender, skip_pause);
+            }
             device::GoXLRUSB::from_device(device, disconnect_sender, event_sender, skip_pause)
         }
     }
@@ -0,0 +1,328 @@
+// Write Scheduling. Sliders being dragged in the UI, or scripts sweeping a volume, can produce
+// dozens of writes a second for the same target where only the last one matters. This wraps a
+// device and holds back writes which are fully superseded by a later write of the same command,
+// replacing them in place if a newer one arrives, then flushes them in the order they were
+// first queued. Anything else (including reads) flushes the queue first, so the device never
+// sees commands out of order. Failures of queued writes are reported from flush_writes, as the
+// request that caused the flush has nothing to do with them.
+use crate::commands::Command;
+use crate::device::base::{
+    AttachGoXLR, ExecutableGoXLR, FullGoXLRDevice, GoXLRCommands, GoXLRDevice, UsbData,
+};
+use anyhow::{Error, Result, bail};
+use log::warn;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::Sender;
+
+// How long a write can be held back waiting for something newer to replace it.
+const COALESCE_WINDOW: Duration = Duration::from_millis(20);
+
+struct PendingWrite {
+    command: Command,
+    body: Vec<u8>,
+    queued: Instant,
+}
+
+pub struct ScheduledGoXLR {
+    goxlr: Box<dyn FullGoXLRDevice>,
+    pending: Vec<PendingWrite>,
+
+    // The last queued write which failed to send, waiting to be reported by flush_writes.
+    failed: Option<Error>,
+}
+
+impl ScheduledGoXLR {
+    pub fn wrap(goxlr: Box<dyn FullGoXLRDevice>) -> Box<dyn FullGoXLRDevice> {
+        Box::new(Self {
+            goxlr,
+            pending: vec![],
+            failed: None,
+        })
+    }
+
+    // Only commands where a new write completely replaces the previous one can be coalesced.
+    fn can_coalesce(command: Command) -> bool {
+        matches!(
+            command,
+            Command::SetChannelVolume(_)
+                | Command::SetSubChannelVolume(_)
+                | Command::SetColourMap()
+                | Command::SetButtonStates()
+        )
+    }
+
+    fn flush(&mut self) {
+        for write in std::mem::take(&mut self.pending) {
+            // Keep going on error, a failure of one write shouldn't drop the others..
+            if let Err(error) = self
+                .goxlr
+                .perform_request(write.command, &write.body, false)
+            {
+                warn!("Failed to send queued {:?}: {}", write.command, error);
+                self.failed = Some(error);
+            }
+        }
+    }
+}
+
+impl AttachGoXLR for ScheduledGoXLR {
+    fn from_device(
+        _device: GoXLRDevice,
+        _disconnect_sender: Sender<String>,
+        _event_sender: Sender<String>,
+        _skip_pause: bool,
+    ) -> Result<Box<dyn FullGoXLRDevice>> {
+        bail!("A Scheduled device must be created by wrapping an existing device");
+    }
+
+    fn set_unique_identifier(&mut self, identifier: String) {
+        self.goxlr.set_unique_identifier(identifier);
+    }
+
+    fn is_connected(&mut self) -> bool {
+        self.goxlr.is_connected()
+    }
+
+    fn set_is_polling(&mut self, polling: bool) {
+        self.goxlr.set_is_polling(polling);
+    }
+}
+
+impl ExecutableGoXLR for ScheduledGoXLR {
+    fn perform_request(&mut self, command: Command, body: &[u8], retry: bool) -> Result<Vec<u8>> {
+        if !Self::can_coalesce(command) {
+            self.flush();
+            return self.goxlr.perform_request(command, body, retry);
+        }
+
+        let command_id = command.command_id();
+        if let Some(write) = self
+            .pending
+            .iter_mut()
+            .find(|write| write.command.command_id() == command_id)
+        {
+            write.body = body.to_vec();
+        } else {
+            self.pending.push(PendingWrite {
+                command,
+                body: body.to_vec(),
+                queued: Instant::now(),
+            });
+        }
+
+        // None of the coalesced commands return anything useful.
+        Ok(vec![])
+    }
+
+    fn get_descriptor(&self) -> Result<UsbData> {
+        self.goxlr.get_descriptor()
+    }
+
+    fn flush_writes(&mut self, force: bool) -> Result<()> {
+        let due = self
+            .pending
+            .first()
+            .is_some_and(|write| write.queued.elapsed() >= COALESCE_WINDOW);
+
+        if force || due {
+            self.flush();
+        }
+
+        if let Some(error) = self.failed.take() {
+            return Err(error);
+        }
+        self.goxlr.flush_writes(force)
+    }
+
+    fn has_pending_writes(&self) -> bool {
+        !self.pending.is_empty() || self.failed.is_some() || self.goxlr.has_pending_writes()
+    }
+}
+
+impl Drop for ScheduledGoXLR {
+    fn drop(&mut self) {
+        // Make sure anything queued (for example, shutdown commands) still reaches the device.
+        self.flush();
+    }
+}
+
+impl GoXLRCommands for ScheduledGoXLR {}
+impl FullGoXLRDevice for ScheduledGoXLR {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::channelstate::ChannelState;
+    use anyhow::anyhow;
+    use goxlr_types::{ChannelName, SubMixChannelName};
+    use std::sync::{Arc, Mutex};
+
+    type Sent = Vec<(Command, Vec<u8>)>;
+
+    // Records every request it's sent, and fails any for the command in `fail`
+    #[derive(Default, Clone)]
+    struct MockGoXLR {
+        sent: Arc<Mutex<Sent>>,
+        fail: Option<Command>,
+    }
+
+    impl MockGoXLR {
+        fn sent(&self) -> Sent {
+            self.sent.lock().unwrap().clone()
+        }
+    }
+
+    impl AttachGoXLR for MockGoXLR {
+        fn from_device(
+            _device: GoXLRDevice,
+            _disconnect_sender: Sender<String>,
+            _event_sender: Sender<String>,
+            _skip_pause: bool,
+        ) -> Result<Box<dyn FullGoXLRDevice>> {
+            bail!("Not Supported");
+        }
+
+        fn set_unique_identifier(&mut self, _identifier: String) {}
+
+        fn is_connected(&mut self) -> bool {
+            true
+        }
+
+        fn set_is_polling(&mut self, _polling: bool) {}
+    }
+
+    impl ExecutableGoXLR for MockGoXLR {
+        fn perform_request(
+            &mut self,
+            command: Command,
+            body: &[u8],
+            _retry: bool,
+        ) -> Result<Vec<u8>> {
+            self.sent.lock().unwrap().push((command, body.to_vec()));
+            if self.fail == Some(command) {
+                return Err(anyhow!("Failed to send {:?}", command));
+            }
+            Ok(vec![1, 2])
+        }
+
+        fn get_descriptor(&self) -> Result<UsbData> {
+            bail!("Not Supported");
+        }
+    }
+
+    impl GoXLRCommands for MockGoXLR {}
+    impl FullGoXLRDevice for MockGoXLR {}
+
+    fn scheduled(mock: &MockGoXLR) -> ScheduledGoXLR {
+        ScheduledGoXLR {
+            goxlr: Box::new(mock.clone()),
+            pending: vec![],
+            failed: None,
+        }
+    }
+
+    const MUSIC: Command = Command::SetChannelVolume(ChannelName::Music);
+    const GAME: Command = Command::SetChannelVolume(ChannelName::Game);
+
+    #[test]
+    fn replaces_pending_write_in_place() -> Result<()> {
+        let mock = MockGoXLR::default();
+        let mut device = scheduled(&mock);
+
+        device.set_volume(ChannelName::Music, 10)?;
+        device.set_volume(ChannelName::Music, 20)?;
+        device.set_volume(ChannelName::Music, 30)?;
+        assert!(mock.sent().is_empty());
+        assert!(device.has_pending_writes());
+
+        device.flush_writes(true)?;
+        assert_eq!(mock.sent(), [(MUSIC, vec![30])]);
+        assert!(!device.has_pending_writes());
+        Ok(())
+    }
+
+    #[test]
+    fn keeps_first_queued_order() -> Result<()> {
+        let mock = MockGoXLR::default();
+        let mut device = scheduled(&mock);
+
+        device.set_volume(ChannelName::Music, 10)?;
+        device.set_volume(ChannelName::Game, 10)?;
+        device.set_volume(ChannelName::Music, 20)?;
+
+        device.flush_writes(true)?;
+        assert_eq!(mock.sent(), [(MUSIC, vec![20]), (GAME, vec![10])]);
+        Ok(())
+    }
+
+    #[test]
+    fn only_flushes_once_the_window_has_passed() -> Result<()> {
+        let mock = MockGoXLR::default();
+        let mut device = scheduled(&mock);
+
+        device.set_sub_volume(SubMixChannelName::Music, 10)?;
+        device.flush_writes(false)?;
+        assert!(mock.sent().is_empty());
+
+        std::thread::sleep(COALESCE_WINDOW);
+        device.flush_writes(false)?;
+        assert_eq!(mock.sent().len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn other_commands_flush_the_queue_first() -> Result<()> {
+        let mock = MockGoXLR::default();
+        let mut device = scheduled(&mock);
+
+        device.set_volume(ChannelName::Music, 10)?;
+        device.set_channel_state(ChannelName::Game, ChannelState::Muted)?;
+        let state = Command::SetChannelState(ChannelName::Game);
+        assert_eq!(mock.sent(), [(MUSIC, vec![10]), (state, vec![1])]);
+
+        // Reads are passed straight through as well, with their response
+        device.set_volume(ChannelName::Game, 10)?;
+        let level = device.request_data(Command::GetMicrophoneLevel, &[])?;
+        assert_eq!(level, [1, 2]);
+
+        let sent = mock.sent();
+        assert_eq!(sent[2], (GAME, vec![10]));
+        assert_eq!(sent[3].0, Command::GetMicrophoneLevel);
+        Ok(())
+    }
+
+    #[test]
+    fn failed_writes_surface_from_flush_writes() -> Result<()> {
+        let mock = MockGoXLR {
+            fail: Some(MUSIC),
+            ..Default::default()
+        };
+        let mut device = scheduled(&mock);
+
+        // The failure belongs to the queued write, not the read that flushed it..
+        device.set_volume(ChannelName::Music, 10)?;
+        device.set_volume(ChannelName::Game, 10)?;
+        device.request_data(Command::GetMicrophoneLevel, &[])?;
+
+        // ..and the writes after it still go out
+        assert_eq!(mock.sent().len(), 3);
+        assert!(device.has_pending_writes());
+        assert!(device.flush_writes(false).is_err());
+
+        // Once reported, it's gone
+        assert!(!device.has_pending_writes());
+        assert!(device.flush_writes(false).is_ok());
+        Ok(())
+    }
+
+    #[test]
+    fn dropping_flushes_the_queue() -> Result<()> {
+        let mock = MockGoXLR::default();
+        let mut device = scheduled(&mock);
+
+        device.set_volume(ChannelName::Music, 10)?;
+        drop(device);
+        assert_eq!(mock.sent(), [(MUSIC, vec![10])]);
+        Ok(())
+    }
+}
@@ -227,6 +227,7 @@ impl AttachGoXLR for TUSBAudioGoXLR {
                 bus_number: 0,
                 address: 0,
                 identifier: self.identifier.clone(),
+                emulated: None,
             });
 
             if new_handle.is_err() {
@@ -874,6 +874,7 @@ pub fn get_devices() -> Vec<GoXLRDevice> {
             bus_number: 0,
             address: 0,
             identifier: Some(device),
+            emulated: None,
         })
     }
     list
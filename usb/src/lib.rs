@@ -6,6 +6,7 @@ pub mod channelstate;
 pub mod colouring;
 pub mod commands;
 pub mod dcp;
+pub mod decoder;
 pub mod devices;
 pub mod error;
 pub mod microphone;
@@ -1,6 +1,7 @@
 use goxlr_types::{InputDevice as BasicInputDevice, OutputDevice as BasicOutputDevice};
+use strum::EnumIter;
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, EnumIter)]
 pub enum OutputDevice {
     HeadphonesRight,
     HeadphonesLeft,
@@ -59,7 +60,7 @@ impl OutputDevice {
     }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, EnumIter)]
 pub enum InputDevice {
     MicrophoneRight,
     MicrophoneLeft,